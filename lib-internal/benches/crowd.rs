@@ -0,0 +1,91 @@
+// lanes of players streaming past eachother in opposite directions,
+// at a fixed density, so that the cost per player should stay flat as the
+// crowd grows
+//
+// the lanes are far enough apart that nobody ever collides, which keeps
+// this about marching, rather than about what players do when they collide
+//
+// run with `cargo bench --bench crowd`
+
+extern crate city_internal;
+
+use std::sync::mpsc;
+use std::time;
+
+use city_internal::entities::player;
+use city_internal::space;
+use city_internal::sulphate;
+use city_internal::units;
+
+const RADIUS: i32 = 10;
+// lanes leave a gap of 30 between players
+const LANE_WIDTH: i32 = 50;
+// the space along a lane for each player, who starts somewhere in the first
+// 60 units of it, leaving at least 20 units between players
+const SPACING: i32 = 80;
+const SPEED: i32 = 20;
+const SIMULATED_SECONDS: i32 = 4;
+
+fn crowd(count: usize) -> time::Duration {
+    let mut space = space::CollisionSpace::new();
+    let mut time = sulphate::EventQueue::new(Default::default());
+    let mut matter = sulphate::EntityHeap::with_seed(count as u64);
+    let (send, recv) = mpsc::channel();
+
+    // roughly as many lanes as there are players in each lane
+    let per_lane = (count as f64).sqrt().ceil() as usize;
+    for n in 0..count {
+        let lane = (n / per_lane) as i32;
+        let start = (n % per_lane) as i32 * SPACING;
+        let offset = matter.random().scalar_between(0.into(), 60.into());
+        let position = units::Position {
+            x: units::Coord::default() + offset + start.into(),
+            y: units::Coord::default() + (lane * LANE_WIDTH).into(),
+        };
+        let direction = if lane % 2 == 0 { 1 } else { -1 };
+        let velocity = units::Velocity {
+            x: (direction * SPEED).into(),
+            y: 0.into(),
+        };
+
+        let mut this = player::Player::new(
+            &mut space,
+            &mut time,
+            &mut matter,
+            position,
+            RADIUS.into(),
+            send.clone(),
+        );
+        let now = this.now();
+        this.body = Some(space::Body::new(position, velocity, now));
+        drop(this);
+
+        // every player sees every other player appear
+        recv.try_iter().count();
+    }
+
+    let mut world = sulphate::World::new(space, matter);
+    let until = time.now() + units::moments(
+        SIMULATED_SECONDS * units::MOMENT_RATE as i32
+    );
+
+    let start = time::Instant::now();
+    time.simulate(&mut world, until);
+    let elapsed = start.elapsed();
+
+    recv.try_iter().count();
+    elapsed
+}
+
+fn main() {
+    for &count in &[500, 1000, 2000, 4000] {
+        let elapsed = crowd(count);
+        let per_player = elapsed / count as u32;
+        println!(
+            "{:>5} players: {:>10.3?} total, {:>8.3?} per player",
+            count,
+            elapsed,
+            per_player,
+        );
+    }
+}
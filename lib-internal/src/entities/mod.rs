@@ -1,21 +1,64 @@
 use std::any;
 
+use space;
 use sulphate;
 use units;
 
 pub use self::player::Player;
+pub use self::wall::Wall;
 
 pub mod player;
+pub mod wall;
 
 #[derive(PartialEq, Clone)]
 pub enum Image {
     Player(player::Image),
+    Wall(wall::Image),
 }
 
 impl Image {
-    pub fn radius(self: &Self) -> units::Distance {
+    pub fn shape(self: &Self) -> space::Shape {
         match *self {
-            Image::Player(_) => 10.into(),
+            Image::Player(ref player) => space::Shape::Circle(player.radius),
+            Image::Wall(ref wall) => wall.shape.clone(),
+        }
+    }
+
+    pub fn filter(self: &Self) -> space::Filter {
+        match *self {
+            Image::Player(ref player) => player.filter,
+            Image::Wall(ref wall) => wall.filter,
+        }
+    }
+
+    pub fn sensor(self: &Self) -> bool {
+        match *self {
+            Image::Player(_) => false,
+            Image::Wall(ref wall) => wall.sensor,
+        }
+    }
+
+    pub fn elastic(self: &Self) -> Option<space::Elastic> {
+        match *self {
+            Image::Player(ref player) => player.elastic,
+            Image::Wall(_) => None,
+        }
+    }
+
+    /// whether this hides whatever is behind it from eyes
+    pub fn opaque(self: &Self) -> bool {
+        match *self {
+            Image::Player(ref player) => player.opaque,
+            Image::Wall(ref wall) => wall.opaque,
+        }
+    }
+
+    /// how far from its centre this can see, where None means everything,
+    /// which only matters for entities with eyes
+    pub fn vision(self: &Self) -> Option<units::Distance> {
+        match *self {
+            Image::Player(ref player) => player.vision,
+            Image::Wall(_) => None,
         }
     }
 }
@@ -30,6 +73,8 @@ pub fn image_of(
 ) -> Option<Image> {
     if uid.ty == any::TypeId::of::<Player>() {
         matter.get::<Player>(uid.id).and_then(Display::image)
+    } else if uid.ty == any::TypeId::of::<Wall>() {
+        matter.get::<Wall>(uid.id).and_then(Display::image)
     } else {
         panic!("Tried to get image of unknown entity");
     }
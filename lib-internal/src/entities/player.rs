@@ -8,14 +8,31 @@ use units;
 pub struct Player {
     // stimulus from the game world
     update: mpsc::Sender<Update>,
+    radius: units::Distance,
+    filter: space::Filter,
+    elastic: Option<space::Elastic>,
+    vision: Option<units::Distance>,
+    opaque: bool,
 }
 
 #[derive(PartialEq, Eq, Clone)]
-pub struct Image;
+pub struct Image {
+    pub radius: units::Distance,
+    pub filter: space::Filter,
+    pub elastic: Option<space::Elastic>,
+    pub vision: Option<units::Distance>,
+    pub opaque: bool,
+}
 
 impl entities::Display for Player {
     fn image(self: &Self) -> Option<entities::Image> {
-        let img = Image;
+        let img = Image {
+            radius: self.radius,
+            filter: self.filter,
+            elastic: self.elastic,
+            vision: self.vision,
+            opaque: self.opaque,
+        };
         Some(entities::Image::Player(img))
     }
 }
@@ -57,22 +74,30 @@ impl Control {
                     space.entry(time, matter, id);
                 let now = this.now();
 
-                // do this borrow before the mutable borrow that follows
+                // do these borrows before the mutable borrow that follows
                 let contact_images = this.get_contact_images();
+                let shape = this.image().map(|image| image.inner_image.shape());
 
-                if let Some(body) = this.body.as_mut() {
+                if let (Some(body), Some(shape)) = (this.body.as_mut(), shape) {
                     let pos = body.position(now);
-                    for image in contact_images {
-                        let rel_vel = velocity - image.body.velocity();
-                        let obst_dir = image.body.position(now) - pos;
-                        let dir = units::Vector::inner(rel_vel, obst_dir);
-
-                        if dir > 0 {
-                            return;  // obstacle in the way in that direction
-                        }
-                    }
+                    let contacts: Vec<_> = contact_images
+                        .iter()
+                        .filter(|image| !image.is_sensor())
+                        .map(|image| {
+                            let normal = shape.normal_towards(
+                                pos,
+                                &image.inner_image.shape(),
+                                image.body.position(now),
+                            );
+                            (normal, image.body.velocity(now))
+                        })
+                        .filter(|&(normal, _)| normal != Default::default())
+                        .collect();
 
-                    body.bounce(velocity, now);
+                    // otherwise pinned in a corner, so ignore the request
+                    if let Some(velocity) = slide(velocity, &contacts) {
+                        body.bounce(velocity, now);
+                    }
                 } else {
                     println!("Player has no location!");
                 }
@@ -81,6 +106,52 @@ impl Control {
     }
 }
 
+// the closest velocity to the one requested that doesn't move into any
+// contact, given as pairs of the direction towards the contact and its
+// velocity
+//
+// if the requested velocity moves into some contacts then it is projected
+// onto the tangent of each in turn, and whichever projection moves into
+// none of them and loses the least speed is chosen, so the result only
+// depends on the order the contacts come in when there is a tie
+fn slide(
+    velocity: units::Velocity,
+    contacts: &[(units::Vector, units::Velocity)],
+) -> Option<units::Velocity> {
+    let approaching = |velocity: units::Velocity, n: usize| {
+        let (normal, obst_vel) = contacts[n];
+        let rel_vel = (velocity - obst_vel).as_vector();
+        units::Vector::wide_inner(rel_vel, normal) > units::Scalar::default()
+    };
+    let blocked: Vec<usize> = (0..contacts.len())
+        .filter(|&n| approaching(velocity, n))
+        .collect();
+    if blocked.is_empty() {
+        return Some(velocity);
+    }
+
+    blocked
+        .into_iter()
+        .filter_map(|n| {
+            let (normal, obst_vel) = contacts[n];
+            let rel_vel = (velocity - obst_vel).as_vector();
+            let along = rel_vel.project_onto(normal);
+            let along = units::Velocity::from_vector(along);
+            let slid = velocity - along;
+            // the contact projected onto is missed by construction,
+            // so don't let rounding errors say otherwise
+            let clear = (0..contacts.len())
+                .all(|other| other == n || !approaching(slid, other));
+            if clear {
+                Some((along.as_vector().wide_squared(), n, slid))
+            } else {
+                None
+            }
+        })
+        .min_by_key(|&(lost, n, _)| (lost, n))
+        .map(|(_, _, slid)| slid)
+}
+
 impl Player {
     fn send(
         self: &Self,
@@ -96,9 +167,22 @@ impl Player {
         time: &'a mut sulphate::EventQueue,
         matter: &'a mut sulphate::EntityHeap,
         position: units::Position,
+        radius: units::Distance,
         update: mpsc::Sender<Update>,
     ) -> space::Entry<'a, Player> {
-        let player = Player { update };
+        assert!(radius > Default::default(), "Players must have a radius");
+        let filter = space::Filter::new(space::Filter::PLAYERS);
+        let elastic = None;
+        let vision = None;
+        let opaque = false;
+        let player = Player {
+            update,
+            radius,
+            filter,
+            elastic,
+            vision,
+            opaque,
+        };
         let id = matter.add(player);
         let mut this = space.entry::<Player>(time, matter, id);
 
@@ -111,6 +195,36 @@ impl Player {
 
         this
     }
+
+    /// changing a player's radius grows or shrinks them in place, touching
+    /// whatever they grow into
+    pub fn set_radius(self: &mut Self, radius: units::Distance) {
+        assert!(radius > Default::default(), "Players must have a radius");
+        self.radius = radius;
+    }
+
+    /// by default players touch everything
+    pub fn set_filter(self: &mut Self, filter: space::Filter) {
+        self.filter = filter;
+    }
+
+    /// by default players stop dead when they collide with anything,
+    /// but elastic players bounce off instead
+    pub fn set_elastic(self: &mut Self, elastic: Option<space::Elastic>) {
+        self.elastic = elastic;
+    }
+
+    /// by default players see everything, but they can be limited to only
+    /// seeing what is within some distance of their centre
+    pub fn set_vision(self: &mut Self, vision: Option<units::Distance>) {
+        self.vision = vision;
+    }
+
+    /// by default players can be seen through, but opaque players hide
+    /// whatever is behind them from the eyes of other players
+    pub fn set_opaque(self: &mut Self, opaque: bool) {
+        self.opaque = opaque;
+    }
 }
 
 impl space::Eyes for Player {
@@ -133,9 +247,16 @@ impl space::Eyes for Player {
 impl space::Collide for Player {
     fn collide(
         mut this: space::Entry<Player>,
-        _other: space::Image,
+        other: space::Image,
     ) {
         let now = this.now();
+        // the space has already bounced elastic players
+        if this.get().is_some_and(|player| player.elastic.is_some()) {
+            return;
+        }
+        if other.is_sensor() {
+            return;
+        }
 
         let body = this.body.as_mut().expect("Collided without a body");
         body.bounce(units::Velocity::default(), now);
@@ -152,3 +273,48 @@ impl space::Collide for Player {
     ) {}
 }
 
+
+#[cfg(test)]
+mod test_slide {
+    use units;
+
+    use super::slide;
+
+    fn vector(x: i32, y: i32) -> units::Vector {
+        units::Vector { x: x.into(), y: y.into() }
+    }
+
+    fn velocity(x: i32, y: i32) -> units::Velocity {
+        units::Velocity { x: x.into(), y: y.into() }
+    }
+
+    #[test]
+    fn test_free() {
+        assert_eq!(slide(velocity(10, 10), &[]), Some(velocity(10, 10)));
+    }
+
+    #[test]
+    fn test_along_wall() {
+        let wall = [(vector(1, 0), velocity(0, 0))];
+        assert_eq!(slide(velocity(10, 10), &wall), Some(velocity(0, 10)));
+        assert_eq!(slide(velocity(-10, 10), &wall), Some(velocity(-10, 10)));
+    }
+
+    #[test]
+    fn test_corner() {
+        let corner = [
+            (vector(1, 0), velocity(0, 0)),
+            (vector(0, 1), velocity(0, 0)),
+        ];
+        assert_eq!(slide(velocity(10, 10), &corner), None);
+        assert_eq!(slide(velocity(-10, 10), &corner), Some(velocity(-10, 0)));
+    }
+
+    #[test]
+    fn test_moving_contact() {
+        // can follow something that is moving away, but no faster than it
+        let ahead = [(vector(1, 0), velocity(5, 0))];
+        assert_eq!(slide(velocity(10, 0), &ahead), Some(velocity(5, 0)));
+        assert_eq!(slide(velocity(3, 0), &ahead), Some(velocity(3, 0)));
+    }
+}
@@ -0,0 +1,94 @@
+use entities;
+use space;
+use sulphate;
+use units;
+
+// walls never move, and aren't affected by anything that touches them
+pub struct Wall {
+    shape: space::Shape,
+    filter: space::Filter,
+    sensor: bool,
+    opaque: bool,
+}
+
+#[derive(PartialEq, Eq, Clone)]
+pub struct Image {
+    pub shape: space::Shape,
+    pub filter: space::Filter,
+    pub sensor: bool,
+    pub opaque: bool,
+}
+
+impl entities::Display for Wall {
+    fn image(self: &Self) -> Option<entities::Image> {
+        let shape = self.shape.clone();
+        let filter = self.filter;
+        let sensor = self.sensor;
+        let opaque = self.opaque;
+        let image = Image { shape, filter, sensor, opaque };
+        Some(entities::Image::Wall(image))
+    }
+}
+
+impl Wall {
+    /// shape must be a segment or a convex polygon with at least three
+    /// corners, given relative to position
+    pub fn new<'a>(
+        space: &'a mut space::CollisionSpace,
+        time: &'a mut sulphate::EventQueue,
+        matter: &'a mut sulphate::EntityHeap,
+        position: units::Position,
+        shape: space::Shape,
+    ) -> space::Entry<'a, Wall> {
+        assert!(shape.radius().is_none(), "Walls can't be circles");
+        assert!(
+            shape.is_convex(),
+            "Wall polygons must be convex, with three corners or more",
+        );
+
+        let filter = space::Filter::new(space::Filter::WALLS);
+        let sensor = false;
+        let opaque = false;
+        let wall = Wall { shape, filter, sensor, opaque };
+        let id = matter.add(wall);
+        let mut this = space.entry::<Wall>(time, matter, id);
+
+        this.body = Some(space::Body::new_frozen(position));
+
+        this
+    }
+
+    /// by default walls touch everything
+    pub fn set_filter(self: &mut Self, filter: space::Filter) {
+        self.filter = filter;
+    }
+
+    /// a sensor wall is a zone, such as a door or a capture area,
+    /// that bodies pass through, while still being told when they overlap
+    pub fn set_sensor(self: &mut Self, sensor: bool) {
+        self.sensor = sensor;
+    }
+
+    /// by default walls can be seen through, but opaque walls hide whatever
+    /// is behind them from eyes, whether or not they are sensors
+    pub fn set_opaque(self: &mut Self, opaque: bool) {
+        self.opaque = opaque;
+    }
+}
+
+impl space::Collide for Wall {
+    fn collide(
+        _this: space::Entry<Wall>,
+        _other: space::Image,
+    ) {}
+
+    fn release(
+        _this: space::Entry<Wall>,
+        _other: space::Image,
+    ) {}
+
+    fn disappear(
+        _this: space::Entry<Wall>,
+        _other: space::Image,
+    ) {}
+}
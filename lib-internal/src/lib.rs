@@ -3,6 +3,9 @@
 extern crate lib_math;
 extern crate rand;
 extern crate sulphate_lib;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
 
 pub mod entities;
 pub mod space;
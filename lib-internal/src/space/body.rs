@@ -1,8 +1,12 @@
 use std::any;
 use std::cmp;
+use std::collections::HashSet;
 
 use entities;
 use space;
+use space::quadratic::{self, Trajectory};
+use space::response;
+use space::shape;
 use sulphate;
 use units;
 
@@ -17,15 +21,18 @@ pub trait Collide: entities::Display + any::Any where Self: Sized {
 
 /* this is quite complicated
  *
- * if an entity hasn't changed position/velocity/radius, then nothing happens
+ * if an entity hasn't changed position/velocity/shape, then nothing happens,
+ * where a change of filter or sensor counts as a change of shape, since it
+ * changes what the entity is in contact with just as much, and so does a
+ * change of opacity, so that the space keeps count of opaque bodies
  *
- * if it changes velocity but neither position nor radius, then a special check
+ * if it changes velocity but neither position nor shape, then a special check
  * occurs over the next instant to see which entities it is currently in
  * contact with
  * this way when you collide with an entity you remain in contact with it,
  * even if the calculations are inaccurate.
  *
- * if it changes either position or radius, then it needs to restart the
+ * if it changes either position or shape, then it needs to restart the
  * ray-march process, checking entities with which it is currently in contact,
  * and checking if it is in contact with stationary objects, whereas both of
  * these things are normally implicit/ignored
@@ -43,17 +50,16 @@ pub fn update_physics(
 
     // do nothing if the image is the same
     if let (Some(n), Some(image)) = (maybe_n, maybe_image) {
-        let c_body = &space.contents[n].1;
+        let c_body = space.at(n).1;
+        let same_shape = c_body.same_shape(&image.inner_image);
 
-        if c_body.body == image.body
-            && c_body.radius == image.inner_image.radius()
-        {
+        if c_body.body == image.body && same_shape {
             return;
         }
 
         let time_now = time.now();
         if c_body.body.position(time_now) == image.body.position(time_now)
-            && c_body.radius == image.inner_image.radius()
+            && same_shape
         {
             bounce = true;
         }
@@ -71,15 +77,23 @@ pub fn update_physics(
             }
         }
 
-        space.contents.remove(n);
+        space.remove(n);
+        space.grid.remove(uid);
     }
 
     // add the new body, giving it the lowest priority:
     // it is responsible for any collisions with currently existent entities
     if let Some(image) = maybe_image {
         let body = image.body.clone();
-        let speed = body.velocity().magnitude();
-        let radius = image.inner_image.radius();
+        let speed = body.velocity(body.last_time).magnitude();
+        let shape = image.inner_image.shape();
+        let filter = image.inner_image.filter();
+        let sensor = image.inner_image.sensor();
+        let opaque = image.inner_image.opaque();
+        assert!(
+            shape.radius().is_some() || !body.is_moving(),
+            "Only circles can move"
+        );
         let physics_state = {
             if bounce {
                 PhysicsState::Bounce(time.now())
@@ -88,8 +102,16 @@ pub fn update_physics(
             }
         };
 
-        let c_body = CollisionBody { body, speed, radius, physics_state };
-        space.contents.push((uid, c_body));
+        let c_body = CollisionBody {
+            body,
+            speed,
+            shape,
+            filter,
+            sensor,
+            opaque,
+            physics_state,
+        };
+        space.push(uid, c_body);
 
         if bounce {
             march(space, time, uid);
@@ -114,13 +136,14 @@ fn march(
     }
     let n = n.unwrap();
 
+    index_body(space, time.now(), n);
     let (march, collisions) =
         get_march_data(space, time.now(), n);
-    apply_march(space, time, uid, n, march);
+    apply_march(space, time, uid, march);
     apply_collisions(space, time, uid, collisions);
 }
 
-// used when the position/radius has changed,
+// used when the position/shape has changed,
 // as such current contacts, and stationary contacts must be considered
 // where they normally would not be
 fn march_relocated(
@@ -137,13 +160,14 @@ fn march_relocated(
     }
     let n = n.unwrap();
 
+    index_body(space, time.now(), n);
     let (march, releases, collisions, new_stable) =
         get_march_relocated_data(space, time.now(), n, contact);
 
     // TODO disappearances and new_stable_contacts can cause problems
     //      since they might cause a second update_physics to be called
     //      similarly if apply_collisions starts calling events directly
-    apply_march(space, time, uid, n, march);
+    apply_march(space, time, uid, march);
     // NOTE this must be called before apply_collisions
     if releases.len() > 0 {
         let before = maybe_before.expect(
@@ -159,14 +183,22 @@ fn apply_march(
     space: &mut space::CollisionSpace,
     time: &mut sulphate::EventQueue,
     uid: sulphate::EntityUId,
-    n: usize,
     march: Option<units::Time>,
 ) {
+    let c_body = space.bodies.get_mut(&uid).unwrap();
+    if let PhysicsState::Bounce(bounce_time) = c_body.physics_state {
+        // the bounce's own MarchEvent will march again in an instant,
+        // until then contact events still need to know about the bounce
+        if bounce_time == time.now() {
+            return;
+        }
+    }
+
     if let Some(march_time) = march {
         let march_event = MarchEvent { uid };
         sulphate::enqueue_absolute(time, march_event, march_time);
     }
-    space.contents[n].1.physics_state = march.map_or(
+    c_body.physics_state = march.map_or(
         PhysicsState::NoMarch,
         |t| PhysicsState::March(t),
     );
@@ -182,11 +214,16 @@ fn apply_collisions(
     for (coll_time, release_time, second_uid) in collisions {
         let first = this.clone();
         let second = ContactData::new(space, second_uid);
+        // bodies can already overlap, e.g. after one of them stops,
+        // in which case anything that should have happened happens now
+        let now = time.now();
         if space.are_in_contact(uid, second_uid) {
             let release_event = ReleaseEvent { first, second };
+            let release_time = cmp::max(release_time, now);
             sulphate::enqueue_absolute(time, release_event, release_time);
-        } else if time.now() < release_time {
+        } else if now < release_time {
             let collide_event = CollideEvent { first, second, release_time };
+            let coll_time = cmp::max(coll_time, now);
             sulphate::enqueue_absolute(time, collide_event, coll_time);
         }
     }
@@ -214,7 +251,6 @@ fn apply_new_stable_contacts(
 ) {
     for other in stable {
         perform_contact(space, time, matter, uid, other, ContactType::Collision);
-        space.in_contact.push((uid, other));
     }
 }
 
@@ -237,18 +273,18 @@ enum MarchResult {
 fn get_march_data(
     space: &space::CollisionSpace,
     time_now: units::Time,
-    n: usize,
+    n: space::Priority,
 ) -> (
     Option<units::Time>,
     Vec<(units::Time, units::Time, sulphate::EntityUId)>,
 ) {
-    let (others, rest) = space.contents.split_at(n);
-    let (_, ref this) = rest[0];
+    let (_, this) = space.at(n);
 
     let mut march = None;
     let mut collisions = Vec::new();
 
-    for &(other_uid, ref other) in others {
+    for m in march_candidates(space, time_now, n, &[]) {
+        let (other_uid, other) = space.at(m);
         use self::MarchResult::*;
         match march_result(this, other, time_now) {
             Miss | StableMiss | StableContact => (),
@@ -261,13 +297,13 @@ fn get_march_data(
         }
     }
 
-    (march, collisions)
+    (cap_march(space, time_now, march), collisions)
 }
 
 fn get_march_relocated_data(
     space: &space::CollisionSpace,
     time_now: units::Time,
-    n: usize,
+    n: space::Priority,
     contacts: Vec<sulphate::EntityUId>,
 ) -> (
     Option<units::Time>,
@@ -276,19 +312,19 @@ fn get_march_relocated_data(
     Vec<sulphate::EntityUId>,
 ) {
     debug_assert_eq!(
-        n + 1, space.contents.len(),
+        Some(&n), space.contents.keys().next_back(),
         "entity's prioirity not reset"
     );
 
-    let (others, rest) = space.contents.split_at(n);
-    let (_, ref this) = rest[0];
+    let (_, this) = space.at(n);
 
     let mut march = None;
     let mut releases = Vec::new();
     let mut collisions = Vec::new();
     let mut stable_collisions = Vec::new();
 
-    for &(other_uid, ref other) in others {
+    for m in march_candidates(space, time_now, n, &contacts) {
+        let (other_uid, other) = space.at(m);
         use self::MarchResult::*;
         match march_result(this, other, time_now) {
             Miss | StableMiss => {
@@ -304,7 +340,7 @@ fn get_march_relocated_data(
             Collide(c, r) => {
                 collisions.push((c, r, other_uid));
                 if contacts.contains(&other_uid) &&
-                    time_now < c || r <= time_now
+                    (time_now < c || r <= time_now)
                 {
                     releases.push(other_uid);
                 }
@@ -318,9 +354,110 @@ fn get_march_relocated_data(
         }
     }
 
+    let march = cap_march(space, time_now, march);
     (march, releases, collisions, stable_collisions)
 }
 
+// the grid only lists a moving body along the path it will take over the
+// next two horizons, so every body marches again within one horizon,
+// while something is moving, and re-lists itself each time it does.
+// this way every listing is still good for a whole horizon after any other
+// body marches
+pub fn index_horizon() -> units::Duration {
+    units::moments(units::MOMENT_RATE as i32)
+}
+
+fn index_body(
+    space: &mut space::CollisionSpace,
+    time_now: units::Time,
+    n: space::Priority,
+) {
+    let bounds;
+    let moving;
+    {
+        let (_, c_body) = space.at(n);
+        moving = c_body.body.is_moving();
+        let end = if moving {
+            time_now + index_horizon() * 2
+        } else {
+            time_now
+        };
+        bounds = swept_bounds(&c_body.body, &c_body.shape, time_now, end);
+    }
+    let uid = space.contents[&n];
+    space.grid.insert(uid, bounds, moving);
+    space.indexed = time_now;
+}
+
+// the bodies listed in the grid near bounds, when they are where they are at
+// time, which is every body unless time is between the last listing and a
+// horizon after it, since only then is every listing sure to be good
+pub fn listed(
+    space: &space::CollisionSpace,
+    bounds: units::Aabb,
+    time: units::Time,
+) -> HashSet<sulphate::EntityUId> {
+    let start = space.indexed;
+    let good = !space.grid.any_moving()
+        || (start <= time && time <= start + index_horizon());
+    if good {
+        space.grid.query(bounds)
+    } else {
+        space.contents.values().cloned().collect()
+    }
+}
+
+// the priorities of the bodies that body n is responsible for, and that are
+// close enough to matter before the next horizon, in priority order
+fn march_candidates(
+    space: &space::CollisionSpace,
+    time_now: units::Time,
+    n: space::Priority,
+    contacts: &[sulphate::EntityUId],
+) -> Vec<space::Priority> {
+    let (_, this) = space.at(n);
+    let end = time_now + index_horizon();
+    let bounds = swept_bounds(&this.body, &this.shape, time_now, end)
+        .expand(march_threshold().as_scalar());
+
+    let mut result: Vec<space::Priority> = space.grid
+        .query(bounds)
+        .into_iter()
+        .chain(contacts.iter().cloned())
+        .flat_map(|uid| space.find_uid(uid))
+        .filter(|&m| m < n)
+        .collect();
+    result.sort();
+    result.dedup();
+    result
+}
+
+// the box containing a body of the given shape from start until end
+pub fn swept_bounds(
+    body: &Body,
+    shape: &space::Shape,
+    start: units::Time,
+    end: units::Time,
+) -> units::Aabb {
+    match shape.radius() {
+        Some(radius) => body.swept_bounds(radius, start, end),
+        None => shape.bounds(body.position(start)),
+    }
+}
+
+fn cap_march(
+    space: &space::CollisionSpace,
+    time_now: units::Time,
+    march: Option<units::Time>,
+) -> Option<units::Time> {
+    if space.grid.any_moving() {
+        let horizon = time_now + index_horizon();
+        Some(march.map_or(horizon, |t| cmp::min(t, horizon)))
+    } else {
+        march
+    }
+}
+
 // this is the edge-to-edge distance at which ray-marching and precise hit-scan
 // are considered equally preferable
 // this is a variable for optimization,
@@ -338,35 +475,70 @@ fn march_result(
     other: &CollisionBody,
     time: units::Time
 ) -> MarchResult {
-    if one.body.velocity() == other.body.velocity() {
+    // filtered out pairs never interact, wherever they are
+    if !one.filter.touches(other.filter) {
+        return MarchResult::StableMiss;
+    }
+    match (one.shape.radius(), other.shape.radius()) {
+        (Some(one_radius), Some(other_radius)) => {
+            let coll_dist = one_radius + other_radius;
+            circle_march_result(one, other, coll_dist, time)
+        },
+        (Some(radius), None) => wall_march_result(one, radius, other, time),
+        (None, Some(radius)) => wall_march_result(other, radius, one, time),
+        // walls never touch eachother
+        (None, None) => MarchResult::StableMiss,
+    }
+}
+
+fn circle_march_result(
+    one: &CollisionBody,
+    other: &CollisionBody,
+    coll_dist: units::Distance,
+    time: units::Time
+) -> MarchResult {
+    let same_velocity = one.body.velocity(time) == other.body.velocity(time);
+    let same_acceleration =
+        one.body.acceleration() == other.body.acceleration();
+    if same_velocity && same_acceleration {
         use self::CollideResult::*;
-        return match collision_stationary(one, other) {
+        let stationary =
+            collision_stationary(&one.body, &other.body, coll_dist, time);
+        return match stationary {
             Collision(_, _) => MarchResult::StableContact,
             Miss => MarchResult::StableMiss,
         };
     }
     let one_pos = one.body.position(time);
     let other_pos = other.body.position(time);
-    let centre_dist_squared = (one_pos - other_pos).squared();
+    let centre_disp = (one_pos - other_pos).as_vector();
+    let centre_dist_squared = centre_disp.wide_squared();
 
     // the maximum distance for testing collision
-    let proximity = one.radius + other.radius + march_threshold();
+    let proximity = coll_dist + march_threshold();
 
     // if they are close enough, check for collision properly
-    if centre_dist_squared < proximity.squared() {
+    if centre_dist_squared < proximity.as_scalar().wide_squared() {
         use self::CollideResult::*;
-        match collision_linear(one, other) {
+        match collision_moving(&one.body, &other.body, coll_dist, time) {
             Collision(Some(t), Some(u)) => MarchResult::Collide(t, u),
             Miss => MarchResult::Miss,
             _ => unreachable!(),
         }
     } else {
         // otherwise march for a while
-        let centre_dist = units::Scalar::rough_sqrt(centre_dist_squared);
+        let centre_dist =
+            units::Distance::from_scalar(centre_dist_squared.sqrt());
         // end up inside the threshold
-        let edge_dist = centre_dist - one.radius - other.radius;
-        // safe since speeds are positive and unequal
-        let march_time = edge_dist / (one.speed + other.speed);
+        let edge_dist = centre_dist - coll_dist;
+        let speed = march_speed(one, time) + march_speed(other, time);
+        // speeds only add to zero if both bodies are just starting to
+        // accelerate from rest, and then too slowly to measure
+        let march_time = if speed == Default::default() {
+            index_horizon()
+        } else {
+            edge_dist / speed
+        };
         // don't subtract the threshold number, so that we
         MarchResult::March(time + march_time)
     }
@@ -380,56 +552,242 @@ enum CollideResult {
     Miss,
 }
 
-fn collision_linear(
-    one: &CollisionBody,
-    other: &CollisionBody,
+// the fastest a body can go until it next marches,
+// which is never more than a horizon away while anything is moving
+fn march_speed(c_body: &CollisionBody, time: units::Time) -> units::Speed {
+    if c_body.body.acceleration() == Default::default() {
+        c_body.speed
+    } else {
+        c_body.body.max_speed(time, time + index_horizon())
+    }
+}
+
+fn collision_moving(
+    one: &Body,
+    other: &Body,
+    coll_dist: units::Distance,
+    time_now: units::Time,
 ) -> CollideResult {
     // we will work with a relative reference frame
     // we use max so that swapping the arguments doesnt change the result
-    let time = cmp::max(one.body.last_time, other.body.last_time);
-    let rel_pos = one.body.position(time) - other.body.position(time);
-    let rel_vel = one.body.velocity() - other.body.velocity();
-
-    // this is the time at which the bodies will be closest
-    // it follows from assuming (p + vt) is orthogonal to v
-    let inner = units::Vector::inner(rel_pos, rel_vel);
-    let near_time: units::Duration = - inner / rel_vel.squared();
+    let time = cmp::max(one.last_time, other.last_time);
+    let rel_pos = one.position(time) - other.position(time);
+    let rel_vel = one.velocity(time) - other.velocity(time);
+    let rel_acc = one.acceleration() - other.acceleration();
+
+    if rel_acc != Default::default() {
+        let trajectory = Trajectory {
+            position: rel_pos,
+            velocity: rel_vel,
+            acceleration: rel_acc,
+        };
+        let start = time_now - time;
+        let end = cmp::max(start, trajectory.escape_time(coll_dist));
+        let contacts = trajectory.circle_contacts(coll_dist, start, end);
+        return first_contact(time, &contacts);
+    }
 
     // they collide when there's no room between their boundaries
-    let coll_dist: units::Distance = one.radius + other.radius;
-    let near: units::Displacement = rel_pos + rel_vel * near_time; 
-    if coll_dist.squared() < near.squared() {
-        CollideResult::Miss
-    } else {
-        // this comes from completing the square in (p + vt)^2 = d^2
-        let diff_squared = (coll_dist.squared() - rel_pos.squared())
-                         / rel_vel.squared()
-                         + near_time.squared();
-        let diff: units::Duration = units::Scalar::sqrt(diff_squared);
-        let contact_time = time + near_time - diff;
-        let release_time = time + near_time + diff + units::instants(1);
+    match shape::circle_contact(rel_pos, rel_vel, coll_dist) {
+        Some((contact, release)) => {
+            let contact_time = time + contact;
+            let release_time = time + release + units::instants(1);
+            CollideResult::Collision(Some(contact_time), Some(release_time))
+        },
+        None => CollideResult::Miss,
+    }
+}
 
-        CollideResult::Collision(Some(contact_time), Some(release_time))
+// accelerating bodies can touch more than once,
+// but only the next contact matters until then
+fn first_contact(
+    time: units::Time,
+    contacts: &[quadratic::Interval],
+) -> CollideResult {
+    match contacts.first() {
+        Some(&(contact, release)) => CollideResult::Collision(
+            Some(time + contact),
+            Some(time + release),
+        ),
+        None => CollideResult::Miss,
     }
 }
 
 fn collision_stationary(
-    one: &CollisionBody,
-    other: &CollisionBody,
+    one: &Body,
+    other: &Body,
+    coll_dist: units::Distance,
+    time: units::Time,
 ) -> CollideResult {
-    let coll_dist = one.radius + other.radius;
-    let centre_disp = one.body.last_position - other.body.last_position;
-    if coll_dist.squared() < centre_disp.squared() {
+    let centre_disp = one.position(time) - other.position(time);
+    let coll_squared = coll_dist.as_scalar().wide_squared();
+    if coll_squared < centre_disp.as_vector().wide_squared() {
         CollideResult::Miss
     } else {
         CollideResult::Collision(None, None)
     }
 }
 
+// walls are static, so this is all relative to the wall's position
+fn wall_march_result(
+    circle: &CollisionBody,
+    radius: units::Distance,
+    wall: &CollisionBody,
+    time: units::Time
+) -> MarchResult {
+    let wall_pos = wall.body.position(time);
+    let dist = wall.shape.wall_distance(circle.body.position(time) - wall_pos);
+    if !circle.body.is_moving() {
+        return if dist <= radius {
+            MarchResult::StableContact
+        } else {
+            MarchResult::StableMiss
+        };
+    }
+
+    if dist < radius + march_threshold() {
+        let wall = (&wall.shape, wall_pos);
+        match collision_wall(&circle.body, radius, wall, time) {
+            CollideResult::Collision(Some(t), Some(u)) =>
+                MarchResult::Collide(t, u),
+            _ => MarchResult::Miss,
+        }
+    } else {
+        // only the circle is moving, so only its speed counts
+        let speed = march_speed(circle, time);
+        let march_time = if speed == Default::default() {
+            index_horizon()
+        } else {
+            (dist - radius) / speed
+        };
+        MarchResult::March(time + march_time)
+    }
+}
+
+// when a moving circle touches a wall, given as a shape and its position
+fn collision_wall(
+    circle: &Body,
+    radius: units::Distance,
+    (shape, wall_pos): (&space::Shape, units::Position),
+    time: units::Time,
+) -> CollideResult {
+    let start = circle.last_time;
+    let rel_pos = circle.position(start) - wall_pos;
+    let vel = circle.velocity(start);
+    let acc = circle.acceleration();
+    if acc != Default::default() {
+        let trajectory = Trajectory {
+            position: rel_pos,
+            velocity: vel,
+            acceleration: acc,
+        };
+        let from = time - start;
+        let reach = radius + shape.reach();
+        let until = cmp::max(from, trajectory.escape_time(reach));
+        let contacts = shape.wall_contacts(trajectory, radius, from, until);
+        return first_contact(start, &contacts);
+    }
+    match shape.wall_contact(rel_pos, vel, radius) {
+        Some((contact, release)) => CollideResult::Collision(
+            Some(start + contact),
+            Some(start + release + units::instants(1)),
+        ),
+        None => CollideResult::Miss,
+    }
+}
+
+/// when two bodies, if neither changes course, are next within margin of
+/// eachother, at or after time, and when they next stop being so
+///
+/// the bodies can be anywhere, not just in a space, and only their shapes
+/// matter, not their filters
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Impact {
+    /// the first moment they are close enough, which is time itself if they
+    /// already are
+    pub contact: units::Time,
+    /// the first moment after that when they are no longer close enough,
+    /// or None if they stay that way forever
+    pub release: Option<units::Time>,
+}
+
+pub fn impact(
+    one: &space::Image,
+    other: &space::Image,
+    margin: units::Distance,
+    time: units::Time,
+) -> Option<Impact> {
+    let one_shape = one.inner_image.shape();
+    let other_shape = other.inner_image.shape();
+    let one = (&one.body, &one_shape);
+    let other = (&other.body, &other_shape);
+    shape_impact(one, other, margin, time)
+}
+
+// as in impact, but for any body with any shape
+pub fn shape_impact(
+    (one, one_shape): (&Body, &space::Shape),
+    (other, other_shape): (&Body, &space::Shape),
+    margin: units::Distance,
+    time: units::Time,
+) -> Option<Impact> {
+    let result = match (one_shape.radius(), other_shape.radius()) {
+        (Some(one_radius), Some(other_radius)) => {
+            let coll_dist = one_radius + other_radius + margin;
+            let same_velocity = one.velocity(time) == other.velocity(time);
+            if same_velocity && one.acceleration() == other.acceleration() {
+                collision_stationary(one, other, coll_dist, time)
+            } else {
+                collision_moving(one, other, coll_dist, time)
+            }
+        },
+        (Some(radius), None) => {
+            let wall = (other_shape, other.position(time));
+            impact_wall(one, radius + margin, wall, time)
+        },
+        (None, Some(radius)) => {
+            let wall = (one_shape, one.position(time));
+            impact_wall(other, radius + margin, wall, time)
+        },
+        // walls never touch eachother
+        (None, None) => CollideResult::Miss,
+    };
+
+    match result {
+        CollideResult::Collision(None, _) => {
+            Some(Impact { contact: time, release: None })
+        },
+        // linear contacts can be over already
+        CollideResult::Collision(_, Some(release)) if release <= time => None,
+        CollideResult::Collision(Some(contact), release) => {
+            let contact = cmp::max(contact, time);
+            Some(Impact { contact, release })
+        },
+        CollideResult::Miss => None,
+    }
+}
+
+// as in collision_wall, but the circle might not be moving
+fn impact_wall(
+    circle: &Body,
+    radius: units::Distance,
+    (shape, wall_pos): (&space::Shape, units::Position),
+    time: units::Time,
+) -> CollideResult {
+    if circle.is_moving() {
+        return collision_wall(circle, radius, (shape, wall_pos), time);
+    }
+    if shape.wall_distance(circle.position(time) - wall_pos) <= radius {
+        CollideResult::Collision(None, None)
+    } else {
+        CollideResult::Miss
+    }
+}
+
 #[derive(Clone, PartialEq)]
 struct ContactData {
     body: space::Body,
-    radius: units::Distance,
+    shape: space::Shape,
     uid: sulphate::EntityUId,
 }
 
@@ -449,9 +807,9 @@ impl ContactData {
     ) -> Option<(Self, Option<units::Time>)> {
         space.get_uid(uid).map(|c_body| {
             let body = c_body.body.clone();
-            let radius = c_body.radius;
+            let shape = c_body.shape.clone();
             (
-                ContactData { body, radius, uid },
+                ContactData { body, shape, uid },
                 if let PhysicsState::Bounce(t) = c_body.physics_state {
                     Some(t)
                 } else {
@@ -466,7 +824,7 @@ impl ContactData {
         other: &Self,
         time: units::Time,
     ) -> bool {
-        self.radius == other.radius &&
+        self.shape == other.shape &&
             self.body.position(time) == other.body.position(time)
     }
 }
@@ -516,17 +874,99 @@ fn perform_contact(
     second_uid: sulphate::EntityUId,
     contact_type: ContactType,
 ) {
-    match contact_type {
+    let pushes = match contact_type {
         ContactType::Collision => {
-            space.in_contact.push((first_uid, second_uid));
+            space.add_contact(first_uid, second_uid);
+            respond(space, time, matter, first_uid, second_uid)
         },
         ContactType::Release | ContactType::Disappear => {
             space.release_contact(first_uid, second_uid);
+            Vec::new()
         },
-    }
+    };
 
     invoke_contact(space, time, matter, first_uid, second_uid, contact_type);
     invoke_contact(space, time, matter, second_uid, first_uid, contact_type);
+
+    // only push once both sides have heard about the contact, so that
+    // neither of them hears about the other's push first
+    for (uid, change) in pushes {
+        push_uid(space, time, matter, uid, change);
+    }
+}
+
+// the changes in velocity that push elastic bodies apart,
+// see response::Elastic
+fn respond(
+    space: &space::CollisionSpace,
+    time: &sulphate::EventQueue,
+    matter: &sulphate::EntityHeap,
+    first_uid: sulphate::EntityUId,
+    second_uid: sulphate::EntityUId,
+) -> Vec<(sulphate::EntityUId, units::Velocity)> {
+    let mut pushes = Vec::new();
+    let is_sensor = |uid| space.get_uid(uid).unwrap().sensor;
+    if is_sensor(first_uid) || is_sensor(second_uid) {
+        return pushes;
+    }
+
+    let elastic_of = |uid| {
+        entities::image_of(matter, uid).and_then(|image| image.elastic())
+    };
+    let first_elastic = elastic_of(first_uid);
+    let second_elastic = elastic_of(second_uid);
+    if first_elastic.is_none() && second_elastic.is_none() {
+        return pushes;
+    }
+
+    let now = time.now();
+    let (first_velocity, second_velocity, normal) = {
+        let first = space.get_uid(first_uid).unwrap();
+        let second = space.get_uid(second_uid).unwrap();
+        let normal = first.shape.normal_towards(
+            first.body.position(now),
+            &second.shape,
+            second.body.position(now),
+        );
+        let first_velocity = first.body.velocity(now);
+        (first_velocity, second.body.velocity(now), normal)
+    };
+    let (first_change, second_change) = response::impulse(
+        first_elastic,
+        first_velocity,
+        second_elastic,
+        second_velocity,
+        normal,
+    );
+
+    if first_change != Default::default() {
+        pushes.push((first_uid, first_change));
+    }
+    if second_change != Default::default() {
+        pushes.push((second_uid, second_change));
+    }
+    pushes
+}
+
+// changes the velocity of uid's body by change, whatever kind of entity it
+// is, since its body belongs to the space
+fn push_uid(
+    space: &mut space::CollisionSpace,
+    time: &mut sulphate::EventQueue,
+    matter: &mut sulphate::EntityHeap,
+    uid: sulphate::EntityUId,
+    change: units::Velocity,
+) {
+    let before = match space.get_uid_image(matter, uid) {
+        Some(before) => before,
+        None => return,
+    };
+    let now = time.now();
+    let mut after = before.clone();
+    let velocity = after.body.velocity(now) + change;
+    let acceleration = after.body.acceleration();
+    after.body.accelerate(velocity, acceleration, now);
+    space::eyes::commit(space, time, matter, uid, Some(&before), Some(&after));
 }
 
 fn perform_disappearance(
@@ -628,15 +1068,26 @@ fn invoke_contact_with(
     with: space::Image,
     contact_type: ContactType,
 ) {
+    let id = this_uid.id;
     if this_uid.ty == any::TypeId::of::<entities::Player>() {
-        let ent = space.entry::<entities::Player>(time, matter, this_uid.id);
+        let ent = space.entry::<entities::Player>(time, matter, id);
+        invoke_collide(ent, with, contact_type);
+    } else if this_uid.ty == any::TypeId::of::<entities::Wall>() {
+        let ent = space.entry::<entities::Wall>(time, matter, id);
+        invoke_collide(ent, with, contact_type);
+    }
+}
 
-        use self::ContactType::*;
-        match contact_type {
-            Collision => Collide::collide(ent, with),
-            Release => Collide::release(ent, with),
-            Disappear => Collide::disappear(ent, with),
-        }
+fn invoke_collide<T>(
+    ent: space::Entry<T>,
+    with: space::Image,
+    contact_type: ContactType,
+) where T: Collide {
+    use self::ContactType::*;
+    match contact_type {
+        Collision => Collide::collide(ent, with),
+        Release => Collide::release(ent, with),
+        Disappear => Collide::disappear(ent, with),
     }
 }
 
@@ -688,9 +1139,26 @@ impl sulphate::Event for MarchEvent {
 
 pub struct CollisionBody {
     pub body: Body,
+    // only meaningful while the body isn't accelerating
     speed: units::Speed,
     physics_state: PhysicsState,
-    radius: units::Distance,
+    pub shape: space::Shape,
+    pub filter: space::Filter,
+    // sensors are told about overlaps, but nothing responds to them
+    pub sensor: bool,
+    // opaque bodies hide whatever is behind them from eyes, see vision
+    pub opaque: bool,
+}
+
+impl CollisionBody {
+    // whether image has the same shape as this, in the sense of
+    // update_physics
+    fn same_shape(self: &Self, image: &entities::Image) -> bool {
+        self.shape == image.shape()
+            && self.filter == image.filter()
+            && self.sensor == image.sensor()
+            && self.opaque == image.opaque()
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -704,6 +1172,7 @@ enum PhysicsState {
 pub struct Body {
     last_position: units::Position,
     current_velocity: units::Velocity,
+    acceleration: units::Acceleration,
     last_time: units::Time,
 }
 
@@ -713,6 +1182,7 @@ impl PartialEq for Body {
         // this is so that rounding errors are propagated consistently
         let pos_eq = self.last_position == other.last_position;
         let vel_eq = self.current_velocity == other.current_velocity;
+        let acc_eq = self.acceleration == other.acceleration;
         let time_eq = self.last_time == other.last_time;
         // stationary objects have the same position regardless of when they
         // became still.
@@ -723,7 +1193,7 @@ impl PartialEq for Body {
                 time_eq
             }
         };
-        pos_eq && vel_eq && time_eq_enough
+        pos_eq && vel_eq && acc_eq && time_eq_enough
     }
 }
 
@@ -735,10 +1205,22 @@ impl Body {
         position: units::Position,
         velocity: units::Velocity,
         time: units::Time
+    ) -> Body {
+        Body::accelerating(position, velocity, Default::default(), time)
+    }
+
+    /// a body whose velocity changes at a constant rate,
+    /// so that its position is quadratic in time
+    pub fn accelerating(
+        position: units::Position,
+        velocity: units::Velocity,
+        acceleration: units::Acceleration,
+        time: units::Time
     ) -> Body {
         Body {
             last_position: position,
             current_velocity: velocity,
+            acceleration,
             last_time: time,
         }
     }
@@ -749,33 +1231,81 @@ impl Body {
         start_time: units::Time,
         travel_time: units::Duration,
     ) -> Body {
-        if travel_time != 0 {
-            Body {
-                last_position: start,
-                current_velocity: (end - start) / travel_time,
-                last_time: start_time,
-            }
+        if travel_time != units::Duration::default() {
+            Body::new(start, (end - start) / travel_time, start_time)
         } else {
             Body::new_frozen(end)
         }
     }
 
     pub fn new_frozen(position: units::Position) -> Body {
-        Body {
-            last_position: position,
-            current_velocity: Default::default(),
-            last_time: Default::default(),
-        }
+        Body::new(position, Default::default(), Default::default())
     }
 
     pub fn position(&self, now: units::Time) -> units::Position {
         let dtime = now - self.last_time;
-        let displacement = self.current_velocity * dtime;
-        self.last_position + displacement
+        let mean_velocity =
+            self.current_velocity + self.acceleration * dtime / 2;
+        self.last_position + mean_velocity * dtime
+    }
+
+    pub fn velocity(&self, now: units::Time) -> units::Velocity {
+        let dtime = now - self.last_time;
+        self.current_velocity + self.acceleration * dtime
+    }
+
+    pub fn acceleration(&self) -> units::Acceleration {
+        self.acceleration
     }
 
-    pub fn velocity(&self) -> units::Velocity {
-        self.current_velocity
+    pub fn is_moving(&self) -> bool {
+        self.current_velocity != Default::default() ||
+            self.acceleration != Default::default()
+    }
+
+    /// the fastest this body goes at any moment from start until end
+    pub fn max_speed(
+        &self,
+        start: units::Time,
+        end: units::Time
+    ) -> units::Speed {
+        // speed is convex in time, so it peaks at one end or the other
+        let start_speed = self.velocity(start).magnitude();
+        if self.acceleration == Default::default() {
+            start_speed
+        } else {
+            cmp::max(start_speed, self.velocity(end).magnitude())
+        }
+    }
+
+    /// a box containing a circle of the given radius, centred on this body,
+    /// at every moment from start until end
+    pub fn swept_bounds(
+        &self,
+        radius: units::Distance,
+        start: units::Time,
+        end: units::Time
+    ) -> units::Aabb {
+        let centre = self.position(start).as_position();
+        let circle = units::Circle { centre, radius: radius.as_scalar() };
+        let mut bounds = circle.swept_bounds(self.position(end).as_position());
+
+        // a curved path can also bulge out wherever it turns around
+        let units::Velocity { x: vel_x, y: vel_y } = self.current_velocity;
+        let units::Acceleration { x: acc_x, y: acc_y } = self.acceleration;
+        for &(vel, acc) in &[(vel_x, acc_x), (vel_y, acc_y)] {
+            let turn = match (-vel).checked_div(acc) {
+                Some(turn) => units::Duration::from_scalar(turn),
+                None => continue,
+            };
+            let turn = self.last_time + turn;
+            if start < turn && turn < end {
+                let centre = self.position(turn).as_position();
+                let circle = units::Circle { centre, ..circle };
+                bounds = bounds.union(circle.bounds());
+            }
+        }
+        bounds
     }
 
     pub fn split(
@@ -783,11 +1313,16 @@ impl Body {
         velocity: units::Velocity,
         now: units::Time
     ) -> Body {
-        Body {
-            last_position: self.position(now),
-            current_velocity: velocity,
-            last_time: now,
-        }
+        self.split_accelerating(velocity, Default::default(), now)
+    }
+
+    pub fn split_accelerating(
+        &self,
+        velocity: units::Velocity,
+        acceleration: units::Acceleration,
+        now: units::Time
+    ) -> Body {
+        Body::accelerating(self.position(now), velocity, acceleration, now)
     }
 
     pub fn split_to(
@@ -804,6 +1339,7 @@ impl Body {
         )
     }
 
+    /// changes velocity, and stops accelerating
     pub fn bounce(
         &mut self,
         velocity: units::Velocity,
@@ -812,6 +1348,15 @@ impl Body {
         *self = self.split(velocity, now);
     }
 
+    pub fn accelerate(
+        &mut self,
+        velocity: units::Velocity,
+        acceleration: units::Acceleration,
+        now: units::Time
+    ) {
+        *self = self.split_accelerating(velocity, acceleration, now);
+    }
+
     pub fn bounce_to(
         &mut self,
         end_point: units::Position,
@@ -826,4 +1371,244 @@ impl Body {
     }
 }
 
+#[cfg(test)]
+mod test_sensors {
+    use entities::wall;
+    use space;
+    use space::testing::{self, Scene};
+    use sulphate;
+    use units;
+
+    struct SetSensor {
+        id: sulphate::EntityId,
+        sensor: bool,
+    }
+
+    impl sulphate::Event for SetSensor {
+        fn invoke(
+            self: Self,
+            space: &mut space::CollisionSpace,
+            time: &mut sulphate::EventQueue,
+            matter: &mut sulphate::EntityHeap,
+        ) {
+            let mut this = space.entry::<wall::Wall>(time, matter, self.id);
+            this.get_mut().unwrap().set_sensor(self.sensor);
+        }
+    }
+
+    // the velocity of an elastic player that heads for a wall, which starts
+    // or stops being a sensor before the player gets there
+    fn velocity_after_toggle(sensor: bool) -> units::Velocity {
+        let mut scene = Scene::new();
+        let shape = space::Shape::Segment(
+            testing::displacement(0, -50),
+            testing::displacement(0, 50),
+        );
+        let wall_position = testing::position(100, 0);
+        let id = scene.wall(wall_position, shape, |wall| {
+            wall.set_sensor(!sensor)
+        });
+        let mass = units::Mass::from_scalar(1.into());
+        let elastic = space::Elastic::new(mass, 1.into());
+        let start = testing::position(0, 0);
+        let (_, recv) = scene.player(start, testing::velocity(10, 0), |p| {
+            p.set_elastic(Some(elastic))
+        });
+
+        let toggle = SetSensor { id, sensor };
+        let when = testing::seconds(5);
+        sulphate::enqueue_absolute(&mut scene.time, toggle, when);
+        scene.run(testing::seconds(20));
+
+        let images = testing::player_images(&recv);
+        let (when, ref image) = *images.last().unwrap();
+        image.body.velocity(when)
+    }
+
+    #[test]
+    fn test_sensor_turned_off() {
+        let velocity = velocity_after_toggle(false);
+        assert_eq!(velocity, testing::velocity(-10, 0));
+    }
+
+    #[test]
+    fn test_sensor_turned_on() {
+        let velocity = velocity_after_toggle(true);
+        assert_eq!(velocity, testing::velocity(10, 0));
+    }
+}
+
+#[cfg(test)]
+mod test_resize {
+    use std::sync::mpsc;
+
+    use entities::player::Player;
+    use space;
+    use space::testing::{self, Scene};
+    use sulphate;
+    use units;
+
+    struct SetRadius {
+        id: sulphate::EntityId,
+        radius: units::Distance,
+    }
+
+    impl sulphate::Event for SetRadius {
+        fn invoke(
+            self: Self,
+            space: &mut space::CollisionSpace,
+            time: &mut sulphate::EventQueue,
+            matter: &mut sulphate::EntityHeap,
+        ) {
+            let mut this = space.entry::<Player>(time, matter, self.id);
+            this.get_mut().unwrap().set_radius(self.radius);
+        }
+    }
+
+    // reports how many bodies a player is touching
+    struct CountContacts {
+        id: sulphate::EntityId,
+        report: mpsc::Sender<usize>,
+    }
 
+    impl sulphate::Event for CountContacts {
+        fn invoke(
+            self: Self,
+            space: &mut space::CollisionSpace,
+            time: &mut sulphate::EventQueue,
+            matter: &mut sulphate::EntityHeap,
+        ) {
+            let this = space.entry::<Player>(time, matter, self.id);
+            self.report.send(this.get_contact_images().len()).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_grow_and_shrink() {
+        // the neighbour drifts away slowly, until the player grows into it
+        let mut scene = Scene::new();
+        let start = testing::position(0, 0);
+        let drift = testing::velocity(-1, 0);
+        let (_, recv) = scene.player(start, drift, |_| ());
+        let still = testing::velocity(0, 0);
+        let grower = testing::position(25, 0);
+        let (id, _) = scene.player(grower, still, |_| ());
+
+        let (report, contacts) = mpsc::channel();
+        for &(second, radius) in &[(1, 20), (3, 10)] {
+            let set = SetRadius { id, radius: radius.into() };
+            let when = testing::seconds(second);
+            sulphate::enqueue_absolute(&mut scene.time, set, when);
+
+            let count = CountContacts { id, report: report.clone() };
+            let when = testing::seconds(second + 1);
+            sulphate::enqueue_absolute(&mut scene.time, count, when);
+        }
+        scene.run(testing::seconds(5));
+
+        // touching once grown, and released once shrunk
+        assert_eq!(contacts.try_iter().collect::<Vec<_>>(), vec![1, 0]);
+
+        // the neighbour collided, which stopped it where it was
+        let images = testing::player_images(&recv);
+        let neighbour: Vec<_> = images
+            .iter()
+            .filter(|&&(when, ref image)| image.body.position(when) != grower)
+            .map(|&(when, ref image)| {
+                (when, image.body.position(when), image.body.velocity(when))
+            })
+            .collect();
+        let expected = vec![
+            (testing::seconds(0), start, drift),
+            (testing::seconds(1), testing::position(-1, 0), still),
+        ];
+        assert_eq!(neighbour, expected);
+    }
+}
+
+#[cfg(test)]
+mod test_impact {
+    use space;
+    use space::testing;
+    use units;
+
+    use super::{shape_impact, Body, Impact};
+
+    fn circle() -> space::Shape {
+        space::Shape::Circle(10.into())
+    }
+
+    fn moving(x: i32, y: i32, velocity: i32) -> Body {
+        let position = testing::position(x, y);
+        Body::new(position, testing::velocity(velocity, 0), testing::seconds(0))
+    }
+
+    fn impact(one: &Body, other: &Body, margin: i32) -> Option<Impact> {
+        let shape = circle();
+        let time = testing::seconds(0);
+        shape_impact((one, &shape), (other, &shape), margin.into(), time)
+    }
+
+    fn instant() -> units::Duration {
+        units::Duration::from_scalar(units::Scalar::from_bits(1))
+    }
+
+    #[test]
+    fn test_head_on() {
+        let impact = impact(&moving(0, 0, 10), &moving(100, 0, -10), 0);
+        let Impact { contact, release } = impact.unwrap();
+        assert_eq!(contact, testing::seconds(4));
+
+        // they pass through eachother, if nothing stops them
+        let release = release.unwrap();
+        assert!(testing::seconds(6) <= release);
+        assert!(release <= testing::seconds(6) + instant());
+    }
+
+    #[test]
+    fn test_margin() {
+        let impact = impact(&moving(0, 0, 10), &moving(100, 0, -10), 20);
+        assert_eq!(impact.unwrap().contact, testing::seconds(3));
+    }
+
+    #[test]
+    fn test_miss() {
+        let passing = impact(&moving(0, 0, 10), &moving(100, 30, -10), 0);
+        assert_eq!(passing, None);
+        let leaving = impact(&moving(0, 0, -10), &moving(100, 0, 0), 0);
+        assert_eq!(leaving, None);
+        let together = impact(&moving(0, 0, 10), &moving(100, 0, 10), 0);
+        assert_eq!(together, None);
+    }
+
+    #[test]
+    fn test_already_touching() {
+        let still = impact(&moving(0, 0, 0), &moving(15, 0, 0), 0);
+        let expected = Impact { contact: testing::seconds(0), release: None };
+        assert_eq!(still, Some(expected));
+
+        let leaving = impact(&moving(0, 0, -5), &moving(15, 0, 0), 0);
+        let Impact { contact, release } = leaving.unwrap();
+        assert_eq!(contact, testing::seconds(0));
+        let release = release.unwrap();
+        assert!(testing::seconds(1) <= release);
+        assert!(release <= testing::seconds(1) + instant());
+    }
+
+    #[test]
+    fn test_wall() {
+        let wall = space::Shape::Segment(
+            testing::displacement(0, -50),
+            testing::displacement(0, 50),
+        );
+        let wall_body = Body::new_frozen(testing::position(100, 0));
+        let shape = circle();
+        let impact = shape_impact(
+            (&moving(0, 0, 10), &shape),
+            (&wall_body, &wall),
+            Default::default(),
+            testing::seconds(0),
+        );
+        assert_eq!(impact.unwrap().contact, testing::seconds(9));
+    }
+}
@@ -53,6 +53,10 @@ impl<'a, T> Entry<'a, T>
         self.matter.get(self.id)
     }
 
+    pub fn id(self: &Self) -> sulphate::EntityId {
+        self.id
+    }
+
     pub fn now(self: &Self) -> units::Time {
         self.time.now()
     }
@@ -67,15 +71,79 @@ impl<'a, T> Entry<'a, T>
             })
     }
 
+    /// sets off along path from wherever the body is now,
+    /// see space::Path
+    pub fn follow(self: &mut Self, path: space::Path) {
+        let leg = {
+            let body = self.body.as_ref().expect(
+                "Tried to follow a path without a body"
+            );
+            space::path::start::<T>(self.time, self.id, body, path)
+        };
+        self.body = Some(leg);
+    }
+
+    /// the first body that a ray from the centre of this body hits, besides
+    /// this body itself, see CollisionSpace::raycast
+    pub fn raycast(
+        self: &Self,
+        direction: units::Vector,
+        max_distance: units::Distance,
+        filter: space::Filter,
+    ) -> Option<space::RayHit> {
+        let now = self.now();
+        let origin = self.body.as_ref().expect(
+            "Tried to cast a ray without a body"
+        ).position(now);
+        let ray = space::ray::Ray { origin, direction, max_distance, filter };
+        space::ray::cast(self.space, ray, now, Some(self.uid()))
+    }
+
+    /// every other body within distance of the centre of this body,
+    /// see CollisionSpace::bodies_within
+    pub fn bodies_within(
+        self: &Self,
+        distance: units::Distance,
+        filter: space::Filter,
+    ) -> Vec<Image> {
+        let now = self.now();
+        let centre = self.body.as_ref().expect(
+            "Tried to look around without a body"
+        ).position(now);
+        let uid = Some(self.uid());
+        space::query::within(
+            self.space, self.matter, centre, distance, now, filter, uid,
+        )
+    }
+
+    /// the count other bodies nearest to the centre of this body,
+    /// see CollisionSpace::nearest_bodies
+    pub fn nearest_bodies(
+        self: &Self,
+        count: usize,
+        filter: space::Filter,
+    ) -> Vec<Image> {
+        let now = self.now();
+        let centre = self.body.as_ref().expect(
+            "Tried to look around without a body"
+        ).position(now);
+        let uid = Some(self.uid());
+        space::query::nearest(
+            self.space, self.matter, centre, count, now, filter, uid,
+        )
+    }
+
     pub fn get_contact_images(self: &Self) -> Vec<Image> {
-        let id = self.id;
-        let ty = any::TypeId::of::<T>();
-        let this_uid = sulphate::EntityUId { id, ty };
-        let contacts = self.space.get_contacts(this_uid);
+        let contacts = self.space.get_contacts(self.uid());
         contacts.into_iter()
                 .flat_map(|uid| self.space.get_uid_image(&self.matter, uid))
                 .collect()
     }
+
+    fn uid(self: &Self) -> sulphate::EntityUId {
+        let ty = any::TypeId::of::<T>();
+        sulphate::EntityUId { id: self.id, ty }
+    }
 }
 
 #[derive(Clone, PartialEq)]
@@ -84,6 +152,34 @@ pub struct Image {
     pub body: space::Body,
 }
 
+impl Image {
+    /// whether this is a sensor, which nothing should react to physically
+    pub fn is_sensor(self: &Self) -> bool {
+        self.inner_image.sensor()
+    }
+
+    /// when this and other next touch, and then stop touching, if neither
+    /// changes course, or None if they never will, see space::Impact
+    pub fn time_of_impact(
+        self: &Self,
+        other: &Image,
+        time: units::Time,
+    ) -> Option<space::Impact> {
+        self.time_within(other, Default::default(), time)
+    }
+
+    /// as in time_of_impact, but for when there is at most distance between
+    /// their edges
+    pub fn time_within(
+        self: &Self,
+        other: &Image,
+        distance: units::Distance,
+        time: units::Time,
+    ) -> Option<space::Impact> {
+        super::body::impact(self, other, distance, time)
+    }
+}
+
 impl<'a, T> Drop for Entry<'a, T>
     where T: any::Any + entities::Display
 {
@@ -92,45 +188,32 @@ impl<'a, T> Drop for Entry<'a, T>
         let val_after = self.image();
         let after = val_after.as_ref();
 
-        let ty = any::TypeId::of::<T>();
-        let id = self.id;
-        let uid = sulphate::EntityUId { ty, id };
-        super::body::update_physics(
-            &mut self.space,
-            &mut self.time,
-            &mut self.matter,
-            uid,
-            after,
-            before,
-        );
-
-        if before != after {
-            let vision_ids: Vec<EyesId> =
-                self.space
-                    .contents
-                    .iter()
-                    .map(|&(uid, _)| uid)
-                    .flat_map(as_eyes)
-                    .collect();
-            for vision_id in vision_ids {
-                update(
-                    vision_id,
-                    &mut self.space,
-                    &mut self.time,
-                    &mut self.matter,
-                    before,
-                    after,
-                );
-            }
-        }
+        let uid = self.uid();
+        commit(self.space, self.time, self.matter, uid, before, after);
+    }
+}
+
+// tells the space that uid has changed from before to after, as an Entry
+// does once it is dropped
+pub fn commit(
+    space: &mut space::CollisionSpace,
+    time: &mut sulphate::EventQueue,
+    matter: &mut sulphate::EntityHeap,
+    uid: sulphate::EntityUId,
+    before: Option<&Image>,
+    after: Option<&Image>,
+) {
+    super::body::update_physics(space, time, matter, uid, after, before);
+    if before != after {
+        super::vision::changed(space, time, matter, uid, before, after);
     }
 }
 
-enum EyesId {
+pub enum EyesId {
     Player(sulphate::EntityId),
 }
 
-fn as_eyes(uid: sulphate::EntityUId) -> Option<EyesId> {
+pub fn as_eyes(uid: sulphate::EntityUId) -> Option<EyesId> {
     if uid.ty == any::TypeId::of::<entities::Player>() {
         Some(EyesId::Player(uid.id))
     } else {
@@ -138,7 +221,7 @@ fn as_eyes(uid: sulphate::EntityUId) -> Option<EyesId> {
     }
 }
 
-fn update(
+pub fn update(
     id: EyesId,
     space: &mut space::CollisionSpace,
     time: &mut sulphate::EventQueue,
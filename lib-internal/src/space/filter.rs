@@ -0,0 +1,74 @@
+/// which bodies can touch eachother
+///
+/// a body belongs to the categories set in `category`,
+/// and only touches bodies in the categories set in `mask`,
+/// so two bodies touch only if each is in the other's mask
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Filter {
+    pub category: u32,
+    pub mask: u32,
+}
+
+impl Filter {
+    pub const PLAYERS: u32 = 1 << 0;
+    pub const WALLS: u32 = 1 << 1;
+    pub const PROJECTILES: u32 = 1 << 2;
+    pub const PICKUPS: u32 = 1 << 3;
+    pub const ALL: u32 = !0;
+
+    /// a body in category that touches everything
+    pub fn new(category: u32) -> Filter {
+        Filter { category, mask: Filter::ALL }
+    }
+
+    pub fn with_mask(self: Filter, mask: u32) -> Filter {
+        Filter { mask, ..self }
+    }
+
+    pub fn touches(self: Filter, other: Filter) -> bool {
+        self.category & other.mask != 0 && other.category & self.mask != 0
+    }
+}
+
+#[cfg(test)]
+mod test_filter {
+    use space;
+    use space::testing::{self, Scene};
+
+    use super::Filter;
+
+    #[test]
+    fn test_touches() {
+        let player = Filter::new(Filter::PLAYERS);
+        let wall = Filter::new(Filter::WALLS);
+        let ghost = player.with_mask(Filter::ALL & !Filter::WALLS);
+        assert!(player.touches(wall));
+        assert!(player.touches(ghost));
+        assert!(!ghost.touches(wall));
+        assert!(!wall.touches(ghost));
+    }
+
+    #[test]
+    fn test_mask_rejects_contact() {
+        let mut scene = Scene::new();
+        let shape = space::Shape::Segment(
+            testing::displacement(0, -50),
+            testing::displacement(0, 50),
+        );
+        scene.wall(testing::position(100, 0), shape, |_| ());
+        let start = testing::position(0, 0);
+        let (_, recv) = scene.player(start, testing::velocity(10, 0), |p| {
+            let filter = Filter::new(Filter::PLAYERS);
+            p.set_filter(filter.with_mask(Filter::ALL & !Filter::WALLS))
+        });
+        scene.run(testing::seconds(20));
+
+        // passes straight through, without ever being stopped
+        let images = testing::player_images(&recv);
+        let (when, ref image) = *images.last().unwrap();
+        assert_eq!(image.body.velocity(when), testing::velocity(10, 0));
+        let end = image.body.position(testing::seconds(20));
+        assert_eq!(end, testing::position(200, 0));
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::{HashMap, HashSet};
+
+use lib_math::Frac;
+
+use sulphate;
+use units;
+
+// a uniform grid of square cells, used as a broad-phase so that marching
+// only has to consider bodies near the marching body
+//
+// a body is listed in every cell that its bounds overlap, but the bounds
+// of a moving body are only good for a while, see body::index_horizon
+
+// cells are 2 ^ CELL_SHIFT units across
+const CELL_SHIFT: u32 = 6;
+
+// bodies covering more cells than this are checked by every query instead
+const MAX_CELLS: i64 = 64;
+
+type Cell = (i64, i64);
+
+struct Entry {
+    min: Cell,
+    max: Cell,
+    moving: bool,
+}
+
+pub struct Grid {
+    cells: HashMap<Cell, Vec<sulphate::EntityUId>>,
+    oversized: Vec<sulphate::EntityUId>,
+    entries: HashMap<sulphate::EntityUId, Entry>,
+    moving: usize,
+    // the box containing every bounds listed since the grid was last empty
+    extent: Option<units::Aabb>,
+}
+
+fn cell_coord(coord: units::Coord) -> i64 {
+    let bits = (coord - units::Coord::default()).into_bits();
+    bits >> (units::Precision::BITS + CELL_SHIFT)
+}
+
+fn cell_of(position: units::Position) -> Cell {
+    (cell_coord(position.x), cell_coord(position.y))
+}
+
+fn cell_count(min: Cell, max: Cell) -> i64 {
+    let width = max.0.saturating_sub(min.0).saturating_add(1);
+    let height = max.1.saturating_sub(min.1).saturating_add(1);
+    width.saturating_mul(height)
+}
+
+impl Grid {
+    pub fn new() -> Grid {
+        let cells = HashMap::new();
+        let oversized = Vec::new();
+        let entries = HashMap::new();
+        let moving = 0;
+        let extent = None;
+        Grid { cells, oversized, entries, moving, extent }
+    }
+
+    /// lists a body in every cell overlapping bounds,
+    /// replacing wherever it was listed before
+    pub fn insert(
+        self: &mut Self,
+        uid: sulphate::EntityUId,
+        bounds: units::Aabb,
+        moving: bool,
+    ) {
+        self.remove(uid);
+
+        let min = cell_of(units::Position::from_position(bounds.min));
+        let max = cell_of(units::Position::from_position(bounds.max));
+        if cell_count(min, max) > MAX_CELLS {
+            self.oversized.push(uid);
+        } else {
+            for x in min.0..max.0 + 1 {
+                for y in min.1..max.1 + 1 {
+                    self.cells.entry((x, y)).or_default().push(uid);
+                }
+            }
+        }
+
+        if moving {
+            self.moving += 1;
+        }
+        self.entries.insert(uid, Entry { min, max, moving });
+        let extent = self.extent.map_or(bounds, |extent| extent.union(bounds));
+        self.extent = Some(extent);
+    }
+
+    pub fn remove(self: &mut Self, uid: sulphate::EntityUId) {
+        let entry = match self.entries.remove(&uid) {
+            Some(entry) => entry,
+            None => return,
+        };
+
+        if entry.moving {
+            self.moving -= 1;
+        }
+        if self.entries.is_empty() {
+            self.extent = None;
+        }
+        if cell_count(entry.min, entry.max) > MAX_CELLS {
+            self.oversized.retain(|&other| other != uid);
+            return;
+        }
+        for x in entry.min.0..entry.max.0 + 1 {
+            for y in entry.min.1..entry.max.1 + 1 {
+                let now_empty = {
+                    let cell = self.cells.get_mut(&(x, y)).expect(
+                        "Grid entry listed in a cell that doesn't exist"
+                    );
+                    cell.retain(|&other| other != uid);
+                    cell.is_empty()
+                };
+                if now_empty {
+                    self.cells.remove(&(x, y));
+                }
+            }
+        }
+    }
+
+    /// every body listed in a cell overlapping bounds, this is a superset of
+    /// the bodies whose bounds actually intersect
+    pub fn query(
+        self: &Self,
+        bounds: units::Aabb,
+    ) -> HashSet<sulphate::EntityUId> {
+        let mut result: HashSet<_> =
+            self.oversized.iter().cloned().collect();
+
+        let min = cell_of(units::Position::from_position(bounds.min));
+        let max = cell_of(units::Position::from_position(bounds.max));
+        if cell_count(min, max) > MAX_CELLS {
+            // cheaper to look at everything than at every cell
+            result.extend(self.entries.keys().cloned());
+            return result;
+        }
+        for x in min.0..max.0 + 1 {
+            for y in min.1..max.1 + 1 {
+                if let Some(cell) = self.cells.get(&(x, y)) {
+                    result.extend(cell.iter().cloned());
+                }
+            }
+        }
+        result
+    }
+
+    /// a box containing the bounds of every listed body, if there are any
+    pub fn extent(self: &Self) -> Option<units::Aabb> {
+        self.extent
+    }
+
+    /// whether any listed body was moving when it was listed
+    pub fn any_moving(self: &Self) -> bool {
+        self.moving > 0
+    }
+}
+
+#[cfg(test)]
+mod test_grid {
+    use std::any;
+
+    use space::testing;
+    use sulphate;
+    use units;
+
+    use super::Grid;
+
+    fn uids(count: usize) -> Vec<sulphate::EntityUId> {
+        let mut matter = sulphate::EntityHeap::with_seed(0);
+        let ty = any::TypeId::of::<()>();
+        (0..count)
+            .map(|_| sulphate::EntityUId { id: matter.add(()), ty })
+            .collect()
+    }
+
+    fn bounds(min: (i32, i32), max: (i32, i32)) -> units::Aabb {
+        let min = testing::position(min.0, min.1).as_position();
+        let max = testing::position(max.0, max.1).as_position();
+        units::Aabb::new(min, max)
+    }
+
+    #[test]
+    fn test_query_nearby() {
+        let uids = uids(2);
+        let mut grid = Grid::new();
+        grid.insert(uids[0], bounds((0, 0), (10, 10)), false);
+        grid.insert(uids[1], bounds((500, 500), (510, 510)), false);
+
+        let found = grid.query(bounds((5, 5), (20, 20)));
+        assert!(found.contains(&uids[0]));
+        assert!(!found.contains(&uids[1]));
+
+        let found = grid.query(bounds((-100, -100), (-90, -90)));
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn test_insert_replaces() {
+        let uids = uids(1);
+        let mut grid = Grid::new();
+        let here = bounds((0, 0), (10, 10));
+        let there = bounds((300, 0), (310, 10));
+        grid.insert(uids[0], here, false);
+        grid.insert(uids[0], there, false);
+        assert!(grid.query(here).is_empty());
+        assert!(grid.query(there).contains(&uids[0]));
+
+        grid.remove(uids[0]);
+        assert!(grid.query(there).is_empty());
+    }
+
+    #[test]
+    fn test_oversized() {
+        let uids = uids(1);
+        let mut grid = Grid::new();
+        grid.insert(uids[0], bounds((0, 0), (10000, 10)), false);
+        let far = bounds((-5000, -5000), (-4990, -4990));
+        assert!(grid.query(far).contains(&uids[0]));
+
+        grid.remove(uids[0]);
+        assert!(grid.query(far).is_empty());
+    }
+
+    #[test]
+    fn test_any_moving() {
+        let uids = uids(2);
+        let mut grid = Grid::new();
+        grid.insert(uids[0], bounds((0, 0), (10, 10)), false);
+        assert!(!grid.any_moving());
+        grid.insert(uids[1], bounds((0, 0), (10, 10)), true);
+        assert!(grid.any_moving());
+        grid.insert(uids[1], bounds((0, 0), (10, 10)), false);
+        assert!(!grid.any_moving());
+    }
+
+    #[test]
+    fn test_extent() {
+        let uids = uids(2);
+        let mut grid = Grid::new();
+        assert_eq!(grid.extent(), None);
+        grid.insert(uids[0], bounds((0, 0), (10, 10)), false);
+        grid.insert(uids[1], bounds((-50, 20), (-40, 30)), false);
+        assert_eq!(grid.extent(), Some(bounds((-50, 0), (10, 30))));
+
+        grid.remove(uids[0]);
+        grid.remove(uids[1]);
+        assert_eq!(grid.extent(), None);
+    }
+}
@@ -1,46 +1,134 @@
 use std::any;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use entities;
 use sulphate;
+use units;
 
 mod body;
 mod eyes;
+mod filter;
+mod grid;
+mod path;
+mod quadratic;
+mod query;
+mod ray;
+mod response;
+mod shape;
+mod vision;
+
+#[cfg(test)]
+mod testing;
 
 pub use self::body::Collide;
 pub use self::body::Body;
+pub use self::body::Impact;
 pub use self::eyes::Entry;
 pub use self::eyes::Eyes;
 pub use self::eyes::Image;
+pub use self::filter::Filter;
+pub use self::path::Path;
+pub use self::ray::RayHit;
+pub use self::response::Elastic;
+pub use self::shape::Shape;
+
+// bodies are ordered by when they were last updated, newer bodies having
+// higher priorities, see body::update_physics
+type Priority = u64;
 
 // a space is a collection of entities with some kind of location-allocation.
 // it is the medium through which entities can communicate psedunymously
 pub struct CollisionSpace {
-    contents: Vec<(sulphate::EntityUId, body::CollisionBody)>,
-    in_contact: Vec<(sulphate::EntityUId, sulphate::EntityUId)>,
+    // the bodies in priority order, and each body under its entity, so that
+    // finding the body of an entity doesn't go through its priority
+    contents: BTreeMap<Priority, sulphate::EntityUId>,
+    bodies: HashMap<sulphate::EntityUId, body::CollisionBody>,
+    priorities: HashMap<sulphate::EntityUId, Priority>,
+    next_priority: Priority,
+    // each contact is listed under both entities
+    in_contact: HashMap<sulphate::EntityUId, BTreeSet<sulphate::EntityUId>>,
+    // what is within range of each pair of eyes, and which of that is hidden
+    // behind something opaque, see vision
+    in_view: HashMap<sulphate::EntityUId, vision::Sight>,
+    hidden: HashMap<sulphate::EntityUId, BTreeSet<sulphate::EntityUId>>,
+    // how far each pair of eyes can see, and when each pair of eyes will
+    // next look at each thing it is waiting to see come or go
+    viewers: vision::Viewers,
+    pending: HashMap<(sulphate::EntityUId, sulphate::EntityUId), units::Time>,
+    // eyes only need to watch for anything moving in the way while there
+    // are opaque bodies to get in the way, and only glance at the pairs of
+    // eyes and targets where something is moving, see vision
+    opaque: usize,
+    watching: BTreeSet<(sulphate::EntityUId, sulphate::EntityUId)>,
+    glancing: bool,
+    // the grid, and the last time anything was listed in it, which is when
+    // its listings start being good, see body::listed
+    grid: grid::Grid,
+    indexed: units::Time,
 }
 
 impl CollisionSpace {
     pub fn new() -> Self {
-        let contents = Vec::new();
-        let in_contact = Vec::new();
-        CollisionSpace { contents, in_contact }
+        let contents = BTreeMap::new();
+        let bodies = HashMap::new();
+        let priorities = HashMap::new();
+        let next_priority = 0;
+        let in_contact = HashMap::new();
+        let in_view = HashMap::new();
+        let hidden = HashMap::new();
+        let viewers = vision::Viewers::new();
+        let pending = HashMap::new();
+        let opaque = 0;
+        let watching = BTreeSet::new();
+        let glancing = false;
+        let grid = grid::Grid::new();
+        let indexed = Default::default();
+        CollisionSpace {
+            contents,
+            bodies,
+            priorities,
+            next_priority,
+            in_contact,
+            in_view,
+            hidden,
+            viewers,
+            pending,
+            opaque,
+            watching,
+            glancing,
+            grid,
+            indexed,
+        }
     }
 
-    fn find<T>(self: &Self, id: sulphate::EntityId) -> Option<usize>
-        where T: any::Any + entities::Display
-    {
-        let ty = any::TypeId::of::<T>();
-        let uid = sulphate::EntityUId { id, ty };
-        self.find_uid(uid)
+    fn find_uid(self: &Self, uid: sulphate::EntityUId) -> Option<Priority> {
+        self.priorities.get(&uid).cloned()
     }
 
-    fn find_uid(self: &Self, uid: sulphate::EntityUId) -> Option<usize> {
-        for (n, &(ent_uid, _)) in self.contents.iter().enumerate() {
-            if uid == ent_uid {
-                return Some(n);
+    // adds a body with a higher priority than any other
+    fn push(
+        self: &mut Self,
+        uid: sulphate::EntityUId,
+        c_body: body::CollisionBody,
+    ) {
+        let n = self.next_priority;
+        self.next_priority += 1;
+        if c_body.opaque {
+            self.opaque += 1;
+        }
+        self.contents.insert(n, uid);
+        self.bodies.insert(uid, c_body);
+        self.priorities.insert(uid, n);
+    }
+
+    fn remove(self: &mut Self, n: Priority) {
+        if let Some(uid) = self.contents.remove(&n) {
+            self.priorities.remove(&uid);
+            match self.bodies.remove(&uid) {
+                Some(ref c_body) if c_body.opaque => self.opaque -= 1,
+                _ => (),
             }
         }
-        None
     }
 
     fn get<T>(
@@ -49,16 +137,24 @@ impl CollisionSpace {
     ) -> Option<&body::CollisionBody>
         where T: any::Any + entities::Display
     {
-        self.find::<T>(id)
-            .map(|n| &self.contents[n].1)
+        let ty = any::TypeId::of::<T>();
+        self.get_uid(sulphate::EntityUId { id, ty })
     }
 
     fn get_uid(
         self: &Self,
         uid: sulphate::EntityUId,
     ) -> Option<&body::CollisionBody> {
-        self.find_uid(uid)
-            .map(|n| &self.contents[n].1)
+        self.bodies.get(&uid)
+    }
+
+    // the entity and body with priority n, which must exist
+    fn at(
+        self: &Self,
+        n: Priority,
+    ) -> (sulphate::EntityUId, &body::CollisionBody) {
+        let uid = self.contents[&n];
+        (uid, &self.bodies[&uid])
     }
 
     fn get_uid_image(
@@ -75,30 +171,23 @@ impl CollisionSpace {
         )
     }
 
-/*
-    fn get_mut<T>(
-        self: &mut Self,
-        id: sulphate::EntityId,
-    ) -> Option<&mut body::CollisionBody>
-        where T: any::Any + entities::Display
-    {
-        self.find::<T>(id)
-            .map(move |n| &mut self.contents[n].1)  // moves self - a reference
-    }
-*/
-
     fn are_in_contact(
         self: &Self,
         first: sulphate::EntityUId,
         second: sulphate::EntityUId,
     ) -> bool {
-        for &(x, y) in &self.in_contact {
-            if first == x && second == y
-            || first == y && second == x {
-                return true;
-            }
-        }
-        false
+        self.in_contact
+            .get(&first)
+            .is_some_and(|contacts| contacts.contains(&second))
+    }
+
+    fn add_contact(
+        self: &mut Self,
+        first: sulphate::EntityUId,
+        second: sulphate::EntityUId,
+    ) {
+        self.in_contact.entry(first).or_default().insert(second);
+        self.in_contact.entry(second).or_default().insert(first);
     }
 
     fn release_contact(
@@ -106,10 +195,25 @@ impl CollisionSpace {
         first: sulphate::EntityUId,
         second: sulphate::EntityUId,
     ) {
-        self.in_contact.retain(|&(x, y)|
-            !(first == x && second == y ||
-                  first == y && second == x)
-        );
+        self.remove_half_contact(first, second);
+        self.remove_half_contact(second, first);
+    }
+
+    fn remove_half_contact(
+        self: &mut Self,
+        from: sulphate::EntityUId,
+        to: sulphate::EntityUId,
+    ) {
+        let now_empty = match self.in_contact.get_mut(&from) {
+            Some(contacts) => {
+                contacts.remove(&to);
+                contacts.is_empty()
+            },
+            None => false,
+        };
+        if now_empty {
+            self.in_contact.remove(&from);
+        }
     }
 
     fn get_contacts(
@@ -117,17 +221,86 @@ impl CollisionSpace {
         uid: sulphate::EntityUId,
     ) -> Vec<sulphate::EntityUId> {
         self.in_contact
-            .iter()
-            .flat_map(|&(first, second)| {
-                if uid == first {
-                    Some(second)
-                } else if uid == second {
-                    Some(first)
-                } else {
-                    None
-                }
+            .get(&uid)
+            .map_or(Vec::new(), |contacts| {
+                contacts.iter().cloned().collect()
             })
-            .collect()
     }
 }
 
+#[cfg(test)]
+mod test_space {
+    use std::any;
+
+    use entities::player;
+    use space;
+    use space::testing::{self, Scene};
+    use sulphate;
+
+    fn player_uid(id: sulphate::EntityId) -> sulphate::EntityUId {
+        let ty = any::TypeId::of::<player::Player>();
+        sulphate::EntityUId { id, ty }
+    }
+
+    #[test]
+    fn test_lookup() {
+        let mut scene = Scene::new();
+        let still = testing::velocity(0, 0);
+        let (first, _) = scene.player(testing::position(0, 0), still, |_| ());
+        let (second, _) = scene.player(testing::position(50, 0), still, |_| ());
+        {
+            let space = &scene.space;
+            let now = scene.time.now();
+            let position = |id| {
+                let c_body = space.get::<player::Player>(id).unwrap();
+                c_body.body.position(now)
+            };
+            assert_eq!(position(first), testing::position(0, 0));
+            assert_eq!(position(second), testing::position(50, 0));
+
+            // newer bodies have higher priorities
+            let first = space.find_uid(player_uid(first)).unwrap();
+            let second = space.find_uid(player_uid(second)).unwrap();
+            assert!(first < second);
+        }
+
+        let mut this = scene.space.entry::<player::Player>(
+            &mut scene.time,
+            &mut scene.matter,
+            first,
+        );
+        this.body = None;
+        drop(this);
+        assert!(scene.space.get::<player::Player>(first).is_none());
+        assert!(scene.space.find_uid(player_uid(first)).is_none());
+        assert!(scene.space.get::<player::Player>(second).is_some());
+        assert_eq!(scene.space.contents.len(), 1);
+    }
+
+    #[test]
+    fn test_contacts() {
+        let mut matter = sulphate::EntityHeap::with_seed(0);
+        let mut uid = || player_uid(matter.add(()));
+        let (a, b, c) = (uid(), uid(), uid());
+        let mut space = space::CollisionSpace::new();
+        space.add_contact(a, b);
+        space.add_contact(c, a);
+        assert!(space.are_in_contact(b, a));
+        assert!(space.are_in_contact(a, c));
+        assert!(!space.are_in_contact(b, c));
+
+        let mut contacts = space.get_contacts(a);
+        contacts.sort();
+        let mut expected = vec![b, c];
+        expected.sort();
+        assert!(contacts == expected);
+
+        space.release_contact(b, a);
+        assert!(!space.are_in_contact(a, b));
+        assert!(space.get_contacts(a) == vec![c]);
+        assert!(space.get_contacts(b).is_empty());
+
+        space.release_contact(a, c);
+        assert!(space.in_contact.is_empty());
+    }
+}
@@ -0,0 +1,244 @@
+use std::any;
+use std::marker;
+
+use entities;
+use space;
+use sulphate;
+use units;
+
+/// a route through timed waypoints, which a body follows in a straight line
+/// from each one to the next, arriving at each at its time, and stopping at
+/// the last
+///
+/// every leg is an ordinary body, so collisions along it work as usual, but
+/// the rest of the route is abandoned as soon as anything else changes the
+/// body, such as a collision that stops it
+///
+/// waypoints that have already passed by the time the body sets off are
+/// skipped, so that a path can be built a little before it is followed
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Path {
+    waypoints: Vec<(units::Time, units::Position)>,
+}
+
+impl Path {
+    pub fn new() -> Path {
+        Path { waypoints: Vec::new() }
+    }
+
+    /// arrive at position at time, after every earlier waypoint and before
+    /// every later one, replacing any other waypoint at the same time
+    pub fn then(
+        mut self: Path,
+        time: units::Time,
+        position: units::Position,
+    ) -> Path {
+        let waypoints = &mut self.waypoints;
+        match waypoints.binary_search_by_key(&time, |&(when, _)| when) {
+            Ok(n) => waypoints[n] = (time, position),
+            Err(n) => waypoints.insert(n, (time, position)),
+        }
+        self
+    }
+
+    pub fn waypoints(self: &Self) -> &[(units::Time, units::Position)] {
+        &self.waypoints
+    }
+}
+
+// the first leg of path, starting from body, with an event to start the
+// next leg when it ends
+pub fn start<T>(
+    time: &mut sulphate::EventQueue,
+    id: sulphate::EntityId,
+    body: &space::Body,
+    mut path: Path,
+) -> space::Body
+    where T: any::Any + entities::Display
+{
+    let now = time.now();
+    path.waypoints.retain(|&(arrival, _)| now < arrival);
+    if path.waypoints.is_empty() {
+        return body.clone();
+    }
+
+    let (arrival, position) = path.waypoints.remove(0);
+    let leg = body.split_to(position, now, arrival);
+
+    let event = LegEvent::<T> {
+        id,
+        leg: leg.clone(),
+        rest: path,
+        _phantom: marker::PhantomData,
+    };
+    sulphate::enqueue_absolute(time, event, arrival);
+    leg
+}
+
+struct LegEvent<T> {
+    id: sulphate::EntityId,
+    leg: space::Body,
+    rest: Path,
+    _phantom: marker::PhantomData<T>,
+}
+
+impl<T> sulphate::Event for LegEvent<T>
+    where T: any::Any + entities::Display
+{
+    fn invoke(
+        self: Self,
+        space: &mut space::CollisionSpace,
+        time: &mut sulphate::EventQueue,
+        matter: &mut sulphate::EntityHeap,
+    ) {
+        let ty = any::TypeId::of::<T>();
+        let uid = sulphate::EntityUId { id: self.id, ty };
+        let current = space.get_uid(uid).map(|c_body| &c_body.body);
+        if current != Some(&self.leg) {
+            return;  // something else has moved it since
+        }
+
+        let now = time.now();
+        let next = if self.rest.waypoints.is_empty() {
+            self.leg.split(Default::default(), now)
+        } else {
+            start::<T>(time, self.id, &self.leg, self.rest)
+        };
+        let mut this = space.entry::<T>(time, matter, self.id);
+        this.body = Some(next);
+    }
+}
+
+#[cfg(test)]
+mod test_paths {
+    use std::sync::mpsc;
+
+    use entities;
+    use space;
+    use space::testing::{self, Scene};
+    use sulphate;
+    use units;
+
+    use super::{start, Path};
+
+    // reports what a player is touching, when it is invoked
+    struct Probe {
+        id: sulphate::EntityId,
+        report: mpsc::Sender<Vec<space::Image>>,
+    }
+
+    impl sulphate::Event for Probe {
+        fn invoke(
+            self: Self,
+            space: &mut space::CollisionSpace,
+            time: &mut sulphate::EventQueue,
+            matter: &mut sulphate::EntityHeap,
+        ) {
+            let this = space.entry::<entities::Player>(time, matter, self.id);
+            self.report.send(this.get_contact_images()).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_then_orders() {
+        let (a, b, c) = (
+            testing::position(0, 0),
+            testing::position(10, 0),
+            testing::position(20, 0),
+        );
+        let path = Path::new()
+            .then(testing::seconds(3), c)
+            .then(testing::seconds(1), a)
+            .then(testing::seconds(2), c)
+            .then(testing::seconds(2), b);
+        let expected = [
+            (testing::seconds(1), a),
+            (testing::seconds(2), b),
+            (testing::seconds(3), c),
+        ];
+        assert_eq!(path.waypoints(), &expected);
+    }
+
+    #[test]
+    fn test_start_skips_the_past() {
+        let mut time = sulphate::EventQueue::new(testing::seconds(2));
+        let id = sulphate::EntityHeap::with_seed(0).add(());
+        let body = space::Body::new_frozen(testing::position(0, 0));
+        let path = Path::new()
+            .then(testing::seconds(1), testing::position(50, 50))
+            .then(testing::seconds(2), testing::position(50, 50))
+            .then(testing::seconds(4), testing::position(20, 0));
+
+        // heads straight for the only waypoint still to come
+        let leg = start::<entities::Player>(&mut time, id, &body, path);
+        let halfway = leg.position(testing::seconds(3));
+        assert_eq!(halfway, testing::position(10, 0));
+
+        let late = Path::new()
+            .then(testing::seconds(1), testing::position(9, 9));
+        let leg = start::<entities::Player>(&mut time, id, &body, late);
+        assert_eq!(leg, body);
+    }
+
+    #[test]
+    fn test_stopped_on_a_later_leg() {
+        let mut scene = Scene::new();
+        let shape = space::Shape::Segment(
+            testing::displacement(-50, 0),
+            testing::displacement(50, 0),
+        );
+        scene.wall(testing::position(100, 42), shape, |_| ());
+        let start = testing::position(0, 0);
+        let (id, recv) = scene.player(start, Default::default(), |_| ());
+        let path = Path::new()
+            .then(testing::seconds(1), testing::position(100, 0))
+            .then(testing::seconds(2), testing::position(100, 128))
+            .then(testing::seconds(3), testing::position(0, 128));
+        scene.space
+            .entry::<entities::Player>(&mut scene.time, &mut scene.matter, id)
+            .follow(path);
+
+        let (report, contacts) = mpsc::channel();
+        let probe = Probe { id, report };
+        sulphate::enqueue_absolute(&mut scene.time, probe, testing::seconds(4));
+        scene.run(testing::seconds(5));
+
+        let images = testing::player_images(&recv);
+        let changes: Vec<_> = images
+            .iter()
+            .map(|&(when, ref image)| {
+                (when, image.body.position(when), image.body.velocity(when))
+            })
+            .collect();
+        // created still, then sets off, and the second leg starts on time,
+        // and runs into the wall once the player is within its radius,
+        // a quarter of the way along
+        let expected = vec![
+            (testing::seconds(0), start, testing::velocity(0, 0)),
+            (testing::seconds(0), start, testing::velocity(100, 0)),
+            (
+                testing::seconds(1),
+                testing::position(100, 0),
+                testing::velocity(0, 128),
+            ),
+            (
+                testing::seconds(1) + units::moments(4),
+                testing::position(100, 32),
+                testing::velocity(0, 0),
+            ),
+        ];
+        assert_eq!(changes, expected);
+
+        // the last leg never starts, so the player is still at the wall
+        let (_, ref last) = *images.last().unwrap();
+        let end = testing::seconds(5);
+        assert_eq!(last.body.position(end), testing::position(100, 32));
+
+        let touching = contacts.recv().unwrap();
+        assert_eq!(touching.len(), 1);
+        match touching[0].inner_image {
+            entities::Image::Wall(_) => (),
+            _ => panic!("Expected to be touching the wall"),
+        }
+    }
+}
@@ -0,0 +1,368 @@
+use std::cmp;
+
+use units;
+
+// contact times for accelerating bodies, whose relative positions are
+// quadratic in time, making the squared distances between them quartic
+//
+// solving these in closed form would need far more bits than a WideScalar,
+// so instead each polynomial is cut into pieces where it only goes one way,
+// at the roots of its derivative, and each piece is bisected down to a
+// single instant
+
+/// a span of durations, from the first moment of contact, until the first
+/// moment after it where they are no longer in contact
+pub type Interval = (units::Duration, units::Duration);
+
+/// the furthest ahead these are ever solved, so that positions never
+/// overflow, this is about 18 hours
+pub fn max_window() -> units::Duration {
+    units::Duration::from_scalar(units::Scalar::from(1 << 16))
+}
+
+/// a point with constant acceleration, relative to wherever it is at the
+/// start of whatever durations it is given
+#[derive(Clone, Copy, Debug)]
+pub struct Trajectory {
+    pub position: units::Displacement,
+    pub velocity: units::Velocity,
+    pub acceleration: units::Acceleration,
+}
+
+impl Trajectory {
+    pub fn position_at(
+        self: Trajectory,
+        duration: units::Duration,
+    ) -> units::Displacement {
+        let mean_velocity = self.velocity + self.acceleration * duration / 2;
+        self.position + mean_velocity * duration
+    }
+
+    pub fn velocity_at(
+        self: Trajectory,
+        duration: units::Duration,
+    ) -> units::Velocity {
+        self.velocity + self.acceleration * duration
+    }
+
+    /// a duration after which the point is further than reach from the
+    /// origin, and never comes back, capped at max_window
+    pub fn escape_time(self: Trajectory, reach: units::Distance)
+        -> units::Duration
+    {
+        // once |a| t^2 / 2 is bigger than |p| + |v| t + reach,
+        // the acceleration alone has carried it out of reach
+        let acc = self.acceleration.as_vector().magnitude();
+        let vel = self.velocity.magnitude().as_scalar();
+        let dist = (self.position.magnitude() + reach).as_scalar();
+        let disc = vel.wide_squared() + (acc * 2).wide_mul(dist);
+        (vel + disc.sqrt())
+            .checked_div(acc)
+            .map(units::Duration::from_scalar)
+            .map_or(max_window(), |escape| cmp::min(escape, max_window()))
+    }
+
+    /// when the point is within radius of the origin, between start and end
+    pub fn circle_contacts(
+        self: Trajectory,
+        radius: units::Distance,
+        start: units::Duration,
+        end: units::Duration,
+    ) -> Vec<Interval> {
+        let acc = self.acceleration.as_vector();
+        let pos_at = |t| self.position_at(t).as_vector();
+        let vel_at = |t| self.velocity_at(t).as_vector();
+
+        // the squared distance, minus the squared radius
+        let coll_squared = radius.as_scalar().wide_squared();
+        let dist = |t| pos_at(t).wide_squared() - coll_squared;
+        // half of its derivative, and then the derivative of that
+        let dist_rate = |t| units::Vector::wide_inner(pos_at(t), vel_at(t));
+        let rate_rate = |t| {
+            let pos_acc = units::Vector::wide_inner(pos_at(t), acc);
+            vel_at(t).wide_squared() + pos_acc
+        };
+
+        // the derivative of rate_rate is linear in time, and zero here
+        let vel = self.velocity.as_vector();
+        let vel_acc = units::Vector::wide_inner(vel, acc);
+        let turn = (-vel_acc).checked_div(acc.wide_squared())
+            .map(units::Duration::from_scalar);
+
+        let splits: Vec<_> = turn.into_iter().collect();
+        let splits = turning_points(&rate_rate, &splits, start, end);
+        let splits = turning_points(&dist_rate, &splits, start, end);
+        nonpositive(&dist, &splits, start, end)
+    }
+
+    /// when project, which should be linear, puts the point between low and
+    /// high, between start and end, where a missing bound is unbounded
+    pub fn range_contacts<P>(
+        self: Trajectory,
+        project: P,
+        (low, high): (Option<units::WideScalar>, Option<units::WideScalar>),
+        start: units::Duration,
+        end: units::Duration,
+    ) -> Vec<Interval>
+        where P: Fn(units::Vector) -> units::WideScalar
+    {
+        let along = |t| project(self.position_at(t).as_vector());
+        let vel = project(self.velocity.as_vector());
+        let acc = project(self.acceleration.as_vector());
+        let turn = (-vel).checked_div(acc).map(units::Duration::from_scalar);
+
+        let splits: Vec<_> = turn.into_iter().collect();
+        let whole = vec![(start, end)];
+        let above_low = low.map_or(whole.clone(), |low| {
+            nonpositive(&|t| low - along(t), &splits, start, end)
+        });
+        let below_high = high.map_or(whole, |high| {
+            nonpositive(&|t| along(t) - high, &splits, start, end)
+        });
+        intersection(&above_low, &below_high)
+    }
+}
+
+pub fn union(one: &[Interval], other: &[Interval]) -> Vec<Interval> {
+    let mut all: Vec<Interval> = one.iter().chain(other).cloned().collect();
+    all.sort();
+    let mut result: Vec<Interval> = Vec::new();
+    for (contact, release) in all {
+        if let Some(last) = result.last_mut() {
+            if contact <= last.1 {
+                last.1 = cmp::max(last.1, release);
+                continue;
+            }
+        }
+        result.push((contact, release));
+    }
+    result
+}
+
+pub fn intersection(one: &[Interval], other: &[Interval]) -> Vec<Interval> {
+    let mut result = Vec::new();
+    let (mut n, mut m) = (0, 0);
+    while n < one.len() && m < other.len() {
+        let contact = cmp::max(one[n].0, other[m].0);
+        let release = cmp::min(one[n].1, other[m].1);
+        if contact < release {
+            result.push((contact, release));
+        }
+        if one[n].1 < other[m].1 {
+            n += 1;
+        } else {
+            m += 1;
+        }
+    }
+    result
+}
+
+// the moments between start and end at which value goes from positive to
+// non-positive or back, given the moments between which it is monotonic
+fn sign_changes<F>(
+    value: &F,
+    splits: &[units::Duration],
+    start: units::Duration,
+    end: units::Duration,
+) -> Vec<units::Duration>
+    where F: Fn(units::Duration) -> units::WideScalar
+{
+    let zero = units::WideScalar::default();
+    let mut points: Vec<_> = splits
+        .iter()
+        .cloned()
+        .filter(|&t| start < t && t < end)
+        .collect();
+    points.sort();
+    points.insert(0, start);
+    points.push(end);
+
+    let mut result = Vec::new();
+    for piece in points.windows(2) {
+        let (low, high) = (piece[0], piece[1]);
+        let positive = value(high) > zero;
+        if (value(low) > zero) != positive {
+            let crossed = |t| (value(t) > zero) == positive;
+            result.push(bisect(low, high, crossed));
+        }
+    }
+    result
+}
+
+// the moments between start and end that split whatever value is the rate
+// of change of into pieces where it only goes one way, given the moments
+// between which value is monotonic
+//
+// these are where value changes sign, along with the instant before each,
+// since the extreme is at one or the other, and a body that only grazes
+// something only touches it at the extreme
+fn turning_points<F>(
+    value: &F,
+    splits: &[units::Duration],
+    start: units::Duration,
+    end: units::Duration,
+) -> Vec<units::Duration>
+    where F: Fn(units::Duration) -> units::WideScalar
+{
+    let instant = units::Duration::from_scalar(units::Scalar::from_bits(1));
+    sign_changes(value, splits, start, end)
+        .into_iter()
+        .flat_map(|change| vec![change - instant, change])
+        .collect()
+}
+
+// the intervals between start and end where value is non-positive,
+// given the moments between which it is monotonic
+fn nonpositive<F>(
+    value: &F,
+    splits: &[units::Duration],
+    start: units::Duration,
+    end: units::Duration,
+) -> Vec<Interval>
+    where F: Fn(units::Duration) -> units::WideScalar
+{
+    let mut inside = value(start) <= units::WideScalar::default();
+    let mut since = start;
+    let mut result = Vec::new();
+    for change in sign_changes(value, splits, start, end) {
+        if inside {
+            result.push((since, change));
+        }
+        since = change;
+        inside = !inside;
+    }
+    if inside {
+        result.push((since, end));
+    }
+    result
+}
+
+// the first moment in (low, high] at which test holds,
+// given that it holds at high but not at low, and only changes once
+fn bisect<F>(
+    low: units::Duration,
+    high: units::Duration,
+    test: F,
+) -> units::Duration
+    where F: Fn(units::Duration) -> bool
+{
+    let bits = |t: units::Duration| t.as_scalar().into_bits() as i128;
+    let duration = |bits: i128| {
+        units::Duration::from_scalar(units::Scalar::from_bits(bits as i64))
+    };
+    let (mut low, mut high) = (bits(low), bits(high));
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        if test(duration(mid)) {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+    duration(high)
+}
+
+#[cfg(test)]
+mod test_quadratic {
+    use space::testing;
+    use units;
+
+    use super::{intersection, union, Interval, Trajectory};
+
+    fn seconds(count: i32) -> units::Duration {
+        units::Duration::from_scalar(count.into())
+    }
+
+    // the smallest duration there is
+    fn instant() -> units::Duration {
+        units::Duration::from_scalar(units::Scalar::from_bits(1))
+    }
+
+    fn wide(value: i32) -> units::WideScalar {
+        units::Scalar::from(value).into()
+    }
+
+    fn along_x(position: i32, velocity: i32, acceleration: i32) -> Trajectory {
+        Trajectory {
+            position: testing::displacement(position, 0),
+            velocity: testing::velocity(velocity, 0),
+            acceleration: units::Acceleration {
+                x: acceleration.into(),
+                y: 0.into(),
+            },
+        }
+    }
+
+    fn contacts(trajectory: Trajectory, radius: i32) -> Vec<Interval> {
+        trajectory.circle_contacts(radius.into(), seconds(0), seconds(30))
+    }
+
+    #[test]
+    fn test_no_acceleration() {
+        // releases as soon as it is further than the radius
+        let expected = vec![(seconds(9), seconds(11) + instant())];
+        assert_eq!(contacts(along_x(-100, 10, 0), 10), expected);
+    }
+
+    #[test]
+    fn test_miss() {
+        let trajectory = Trajectory {
+            position: testing::displacement(-100, 20),
+            ..along_x(-100, 10, 0)
+        };
+        assert_eq!(contacts(trajectory, 10), vec![]);
+    }
+
+    #[test]
+    fn test_passes_twice() {
+        // -50 + 20t - t^2 goes out past 14 and then comes back
+        let expected = vec![
+            (seconds(2), seconds(4) + instant()),
+            (seconds(16), seconds(18) + instant()),
+        ];
+        assert_eq!(contacts(along_x(-50, 20, -2), 14), expected);
+    }
+
+    #[test]
+    fn test_grazing() {
+        // 100 - 20t + 2t^2 turns around exactly 50 away, at 5s
+        let expected = vec![(seconds(5), seconds(5) + instant())];
+        assert_eq!(contacts(along_x(100, -20, 4), 50), expected);
+    }
+
+    #[test]
+    fn test_range() {
+        let project = |point: units::Vector| units::WideScalar::from(point.x);
+        let bounds = (Some(wide(-10)), Some(wide(10)));
+        let still = along_x(-100, 10, 0);
+        let found =
+            still.range_contacts(project, bounds, seconds(0), seconds(30));
+        assert_eq!(found, vec![(seconds(9), seconds(11) + instant())]);
+
+        // never reaches 10, so is never above it, and is below it forever
+        let turning = along_x(-100, 20, -2);
+        let above = (Some(wide(10)), None);
+        let found =
+            turning.range_contacts(project, above, seconds(0), seconds(30));
+        assert_eq!(found, vec![]);
+        let below = (None, Some(wide(10)));
+        let found =
+            turning.range_contacts(project, below, seconds(0), seconds(30));
+        assert_eq!(found, vec![(seconds(0), seconds(30))]);
+    }
+
+    #[test]
+    fn test_union_and_intersection() {
+        let one = vec![(seconds(0), seconds(2)), (seconds(4), seconds(6))];
+        let other = vec![(seconds(1), seconds(5))];
+        assert_eq!(union(&one, &other), vec![(seconds(0), seconds(6))]);
+        let both = vec![(seconds(1), seconds(2)), (seconds(4), seconds(5))];
+        assert_eq!(intersection(&one, &other), both);
+
+        // touching at an instant isn't overlapping
+        let after = vec![(seconds(2), seconds(3))];
+        let joined = vec![(seconds(0), seconds(3)), (seconds(4), seconds(6))];
+        assert_eq!(union(&one, &after), joined);
+        assert_eq!(intersection(&one, &after), vec![]);
+    }
+}
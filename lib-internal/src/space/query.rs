@@ -0,0 +1,314 @@
+use std::collections::HashSet;
+
+use space;
+use sulphate;
+use units;
+
+// as with raycast, these look bodies up in the grid, so a query about any
+// time other than now, or very shortly after, checks every body instead
+
+impl space::CollisionSpace {
+    /// every body that filter touches, and that any part of is within
+    /// distance of centre at time, oldest first
+    pub fn bodies_within(
+        self: &Self,
+        matter: &sulphate::EntityHeap,
+        centre: units::Position,
+        distance: units::Distance,
+        time: units::Time,
+        filter: space::Filter,
+    ) -> Vec<space::Image> {
+        within(self, matter, centre, distance, time, filter, None)
+    }
+
+    /// every body that filter touches, and that any part of is inside
+    /// bounds at time, oldest first
+    pub fn bodies_in(
+        self: &Self,
+        matter: &sulphate::EntityHeap,
+        bounds: units::Aabb,
+        time: units::Time,
+        filter: space::Filter,
+    ) -> Vec<space::Image> {
+        let listed = space::body::listed(self, bounds, time);
+        let found = candidates(self, listed, filter, None)
+            .into_iter()
+            .filter(|&n| {
+                let (_, c_body) = self.at(n);
+                c_body.shape.overlaps(c_body.body.position(time), bounds)
+            })
+            .collect();
+        images(self, matter, found)
+    }
+
+    /// the count bodies that filter touches, whose nearest parts are nearest
+    /// to centre at time, nearest first
+    pub fn nearest_bodies(
+        self: &Self,
+        matter: &sulphate::EntityHeap,
+        centre: units::Position,
+        count: usize,
+        time: units::Time,
+        filter: space::Filter,
+    ) -> Vec<space::Image> {
+        nearest(self, matter, centre, count, time, filter, None)
+    }
+}
+
+// as in bodies_within, but leaving out except
+pub fn within(
+    space: &space::CollisionSpace,
+    matter: &sulphate::EntityHeap,
+    centre: units::Position,
+    distance: units::Distance,
+    time: units::Time,
+    filter: space::Filter,
+    except: Option<sulphate::EntityUId>,
+) -> Vec<space::Image> {
+    let bounds = units::Aabb::from_point(centre.as_position())
+        .expand(distance.as_scalar());
+    let listed = space::body::listed(space, bounds, time);
+    let found = candidates(space, listed, filter, except)
+        .into_iter()
+        .filter(|&n| {
+            let (_, c_body) = space.at(n);
+            let point = centre - c_body.body.position(time);
+            c_body.shape.distance(point) <= distance
+        })
+        .collect();
+    images(space, matter, found)
+}
+
+// as in nearest_bodies, but leaving out except
+pub fn nearest(
+    space: &space::CollisionSpace,
+    matter: &sulphate::EntityHeap,
+    centre: units::Position,
+    count: usize,
+    time: units::Time,
+    filter: space::Filter,
+    except: Option<sulphate::EntityUId>,
+) -> Vec<space::Image> {
+    if count == 0 {
+        return Vec::new();
+    }
+
+    // look further and further out, until there are enough bodies within
+    // reach, since anything nearer than them must have been listed too,
+    // or until the grid gives up and lists everything
+    let mut reach = units::Distance::from_scalar(64.into());
+    loop {
+        let bounds = units::Aabb::from_point(centre.as_position())
+            .expand(reach.as_scalar());
+        let listed = space::body::listed(space, bounds, time);
+        let everything = listed.len() >= space.contents.len();
+        let mut found: Vec<_> = candidates(space, listed, filter, except)
+            .into_iter()
+            .map(|n| {
+                let (_, c_body) = space.at(n);
+                let point = centre - c_body.body.position(time);
+                (c_body.shape.distance(point), n)
+            })
+            .collect();
+        // ties go to whichever body was there first
+        found.sort();
+
+        let enough = found.len() >= count && found[count - 1].0 <= reach;
+        if enough || everything {
+            let found = found
+                .into_iter()
+                .take(count)
+                .map(|(_, n)| n)
+                .collect();
+            return images(space, matter, found);
+        }
+        reach = reach + reach;
+    }
+}
+
+// the priorities of the listed bodies that filter touches, besides except,
+// in priority order
+fn candidates(
+    space: &space::CollisionSpace,
+    listed: HashSet<sulphate::EntityUId>,
+    filter: space::Filter,
+    except: Option<sulphate::EntityUId>,
+) -> Vec<space::Priority> {
+    let mut result: Vec<_> = listed
+        .into_iter()
+        .filter(|&uid| Some(uid) != except)
+        .flat_map(|uid| space.find_uid(uid))
+        .filter(|n| filter.touches(space.at(*n).1.filter))
+        .collect();
+    result.sort();
+    result
+}
+
+fn images(
+    space: &space::CollisionSpace,
+    matter: &sulphate::EntityHeap,
+    found: Vec<space::Priority>,
+) -> Vec<space::Image> {
+    found
+        .into_iter()
+        .flat_map(|n| space.get_uid_image(matter, space.contents[&n]))
+        .collect()
+}
+
+#[cfg(test)]
+mod test_queries {
+    use space;
+    use space::testing::{self, Scene};
+    use units;
+
+    // players along the x axis, at each of xs in turn
+    fn scene(xs: &[i32]) -> Scene {
+        let mut scene = Scene::new();
+        for &x in xs {
+            let still = testing::velocity(0, 0);
+            scene.player(testing::position(x, 0), still, |_| ());
+        }
+        scene
+    }
+
+    fn positions(
+        scene: &Scene,
+        images: Vec<space::Image>,
+    ) -> Vec<units::Position> {
+        let now = scene.time.now();
+        images.into_iter().map(|image| image.body.position(now)).collect()
+    }
+
+    fn everything() -> space::Filter {
+        space::Filter::new(space::Filter::PROJECTILES)
+    }
+
+    #[test]
+    fn test_nearest_order() {
+        let scene = scene(&[300, 50, 1000, 150]);
+        let found = scene.space.nearest_bodies(
+            &scene.matter,
+            testing::position(0, 0),
+            3,
+            scene.time.now(),
+            everything(),
+        );
+        let expected = vec![
+            testing::position(50, 0),
+            testing::position(150, 0),
+            testing::position(300, 0),
+        ];
+        assert_eq!(positions(&scene, found), expected);
+    }
+
+    #[test]
+    fn test_nearest_ties() {
+        // equally near, so the one that was there first comes first
+        let scene = scene(&[60, -60, 20]);
+        let found = scene.space.nearest_bodies(
+            &scene.matter,
+            testing::position(0, 0),
+            2,
+            scene.time.now(),
+            everything(),
+        );
+        let expected = vec![testing::position(20, 0), testing::position(60, 0)];
+        assert_eq!(positions(&scene, found), expected);
+
+        let none = scene.space.nearest_bodies(
+            &scene.matter,
+            testing::position(0, 0),
+            0,
+            scene.time.now(),
+            everything(),
+        );
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn test_within() {
+        // oldest first, whatever the distance, and reaching their edges
+        let scene = scene(&[300, 150, 50, 165]);
+        let found = scene.space.bodies_within(
+            &scene.matter,
+            testing::position(0, 0),
+            155.into(),
+            scene.time.now(),
+            everything(),
+        );
+        let expected = vec![
+            testing::position(150, 0),
+            testing::position(50, 0),
+            testing::position(165, 0),
+        ];
+        assert_eq!(positions(&scene, found), expected);
+
+        let walls = space::Filter::new(space::Filter::PLAYERS)
+            .with_mask(space::Filter::WALLS);
+        let found = scene.space.bodies_within(
+            &scene.matter,
+            testing::position(0, 0),
+            155.into(),
+            scene.time.now(),
+            walls,
+        );
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn test_in_bounds() {
+        let scene = scene(&[0, 100, 200]);
+        let bounds = units::Aabb::new(
+            testing::position(95, -5).as_position(),
+            testing::position(195, 5).as_position(),
+        );
+        let found = scene.space.bodies_in(
+            &scene.matter,
+            bounds,
+            scene.time.now(),
+            everything(),
+        );
+        let expected = vec![
+            testing::position(100, 0),
+            testing::position(200, 0),
+        ];
+        assert_eq!(positions(&scene, found), expected);
+    }
+
+    #[test]
+    fn test_far_future() {
+        // long after the grid has stopped listing where it will be
+        let mut scene = Scene::new();
+        let start = testing::position(0, -500);
+        scene.player(start, testing::velocity(0, 50), |_| ());
+        let later = testing::seconds(10);
+        let centre = testing::position(0, 0);
+
+        let within = scene.space.bodies_within(
+            &scene.matter,
+            centre,
+            20.into(),
+            later,
+            everything(),
+        );
+        assert_eq!(within.len(), 1);
+
+        let bounds = units::Aabb::from_point(centre.as_position());
+        let inside = scene.space.bodies_in(
+            &scene.matter,
+            bounds,
+            later,
+            everything(),
+        );
+        assert_eq!(inside.len(), 1);
+
+        let nearest = scene.space.nearest_bodies(
+            &scene.matter,
+            centre,
+            1,
+            later,
+            everything(),
+        );
+        assert_eq!(nearest.len(), 1);
+    }
+}
@@ -0,0 +1,227 @@
+use space;
+use sulphate;
+use units;
+
+/// the first thing a ray hits, see CollisionSpace::raycast
+#[derive(Clone, PartialEq)]
+pub struct RayHit {
+    pub uid: sulphate::EntityUId,
+    pub body: space::Body,
+    pub position: units::Position,
+    /// the unit vector pointing out of the surface that was hit,
+    /// or zero if the ray started inside the body
+    pub normal: units::Vector,
+    pub distance: units::Distance,
+}
+
+impl space::CollisionSpace {
+    /// the first body that a ray from origin hits, going in direction for at
+    /// most max_distance, with every body where it is at time
+    ///
+    /// the ray only hits bodies that filter touches, and passes through
+    /// sensors, and a ray without a direction hits nothing
+    ///
+    /// bodies are only indexed around the present, so a ray at any other
+    /// time than now, or very shortly after, checks every body, which is
+    /// much slower
+    pub fn raycast(
+        self: &Self,
+        origin: units::Position,
+        direction: units::Vector,
+        max_distance: units::Distance,
+        time: units::Time,
+        filter: space::Filter,
+    ) -> Option<RayHit> {
+        let ray = Ray { origin, direction, max_distance, filter };
+        cast(self, ray, time, None)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Ray {
+    pub origin: units::Position,
+    pub direction: units::Vector,
+    pub max_distance: units::Distance,
+    pub filter: space::Filter,
+}
+
+// as in CollisionSpace::raycast, but passing through the body of except,
+// which is usually whoever the ray is coming from
+pub fn cast(
+    space: &space::CollisionSpace,
+    ray: Ray,
+    time: units::Time,
+    except: Option<sulphate::EntityUId>,
+) -> Option<RayHit> {
+    let direction = ray.direction.normalize();
+    if direction == units::Vector::default() {
+        return None;
+    }
+    let reach = units::Displacement::from_vector(
+        direction.with_length(ray.max_distance.as_scalar())
+    );
+    let bounds = units::Aabb::new(
+        ray.origin.as_position(),
+        (ray.origin + reach).as_position(),
+    );
+
+    let hit = space::body::listed(space, bounds, time)
+        .into_iter()
+        .filter(|&uid| Some(uid) != except)
+        .flat_map(|uid| space.find_uid(uid))
+        .flat_map(|n| {
+            let (uid, c_body) = space.at(n);
+            if c_body.sensor || !ray.filter.touches(c_body.filter) {
+                return None;
+            }
+            let origin = ray.origin - c_body.body.position(time);
+            match c_body.shape.ray_contact(origin, direction) {
+                Some((distance, normal)) if distance <= ray.max_distance => {
+                    Some((distance, n, uid, normal))
+                },
+                _ => None,
+            }
+        })
+        // ties go to whichever body was there first
+        .min_by_key(|&(distance, n, _, _)| (distance, n));
+
+    hit.map(|(distance, n, uid, normal)| {
+        let body = space.at(n).1.body.clone();
+        let travelled = direction.with_length(distance.as_scalar());
+        let position =
+            ray.origin + units::Displacement::from_vector(travelled);
+        RayHit { uid, body, position, normal, distance }
+    })
+}
+
+
+#[cfg(test)]
+mod test_raycast {
+    use std::any;
+
+    use entities::wall;
+    use space;
+    use space::testing::{self, Scene};
+    use sulphate;
+    use units;
+
+    use super::RayHit;
+
+    fn post() -> space::Shape {
+        space::Shape::Segment(
+            testing::displacement(0, -50),
+            testing::displacement(0, 50),
+        )
+    }
+
+    fn cast_right(scene: &Scene, distance: i32) -> Option<RayHit> {
+        scene.space.raycast(
+            testing::position(0, 0),
+            units::Vector { x: 1.into(), y: 0.into() },
+            distance.into(),
+            scene.time.now(),
+            space::Filter::new(space::Filter::PROJECTILES),
+        )
+    }
+
+    fn left() -> units::Vector {
+        units::Vector { x: (-1).into(), y: 0.into() }
+    }
+
+    fn is_wall(hit: &RayHit, id: sulphate::EntityId) -> bool {
+        hit.uid.id == id && hit.uid.ty == any::TypeId::of::<wall::Wall>()
+    }
+
+    #[test]
+    fn test_first_hit() {
+        let mut scene = Scene::new();
+        scene.wall(testing::position(200, 0), post(), |_| ());
+        let near = scene.wall(testing::position(100, 0), post(), |_| ());
+
+        let hit = cast_right(&scene, 500).unwrap();
+        assert!(is_wall(&hit, near));
+        assert_eq!(hit.distance, 100.into());
+        assert_eq!(hit.position, testing::position(100, 0));
+        assert_eq!(hit.normal, left());
+
+        assert!(cast_right(&scene, 99).is_none());
+    }
+
+    #[test]
+    fn test_passes_sensors_and_filtered() {
+        let mut scene = Scene::new();
+        scene.wall(testing::position(100, 0), post(), |wall| {
+            wall.set_sensor(true)
+        });
+        scene.wall(testing::position(150, 0), post(), |wall| {
+            let filter = space::Filter::new(space::Filter::WALLS);
+            let mask = space::Filter::ALL & !space::Filter::PROJECTILES;
+            wall.set_filter(filter.with_mask(mask))
+        });
+        let last = scene.wall(testing::position(200, 0), post(), |_| ());
+
+        let hit = cast_right(&scene, 500).unwrap();
+        assert!(is_wall(&hit, last));
+        assert_eq!(hit.distance, 200.into());
+    }
+
+    #[test]
+    fn test_circle() {
+        let mut scene = Scene::new();
+        let still = testing::velocity(0, 0);
+        scene.player(testing::position(100, 0), still, |_| ());
+
+        let hit = cast_right(&scene, 500).unwrap();
+        assert_eq!(hit.distance, 90.into());
+        assert_eq!(hit.position, testing::position(90, 0));
+        assert_eq!(hit.normal, left());
+    }
+
+    #[test]
+    fn test_starts_inside() {
+        let mut scene = Scene::new();
+        let square = space::Shape::Polygon(vec![
+            testing::displacement(-10, -10),
+            testing::displacement(10, -10),
+            testing::displacement(10, 10),
+            testing::displacement(-10, 10),
+        ]);
+        scene.wall(testing::position(5, 0), square, |_| ());
+
+        let hit = cast_right(&scene, 500).unwrap();
+        assert_eq!(hit.distance, 0.into());
+        assert_eq!(hit.normal, Default::default());
+    }
+
+    #[test]
+    fn test_no_direction() {
+        let mut scene = Scene::new();
+        scene.wall(testing::position(5, 0), post(), |_| ());
+
+        let hit = scene.space.raycast(
+            testing::position(0, 0),
+            Default::default(),
+            500.into(),
+            scene.time.now(),
+            space::Filter::new(space::Filter::PROJECTILES),
+        );
+        assert!(hit.is_none());
+    }
+
+    #[test]
+    fn test_far_future() {
+        // long after the grid has stopped listing where it will be
+        let mut scene = Scene::new();
+        let start = testing::position(100, -500);
+        scene.player(start, testing::velocity(0, 50), |_| ());
+
+        let hit = scene.space.raycast(
+            testing::position(0, 0),
+            units::Vector { x: 1.into(), y: 0.into() },
+            500.into(),
+            testing::seconds(10),
+            space::Filter::new(space::Filter::PROJECTILES),
+        );
+        assert_eq!(hit.unwrap().distance, 90.into());
+    }
+}
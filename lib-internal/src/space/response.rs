@@ -0,0 +1,179 @@
+use std::cmp;
+
+use units;
+
+/// an opt-in physical response to collisions, where bodies push eachother
+/// apart, rather than being left to their `Collide` implementations.
+///
+/// bodies without one, such as walls, can't be pushed,
+/// and act as though they had infinite mass
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Elastic {
+    mass: units::Mass,
+    restitution: units::Scalar,
+}
+
+impl Elastic {
+    /// restitution is the fraction of their approach speed that two bodies
+    /// separate at, from 0 for bodies that stop dead against eachother,
+    /// to 1 for bodies that lose no energy at all
+    pub fn new(mass: units::Mass, restitution: units::Scalar) -> Elastic {
+        assert!(mass > Default::default(), "Elastic body without mass");
+        let range = units::Scalar::default()..=units::Scalar::from(1);
+        assert!(
+            range.contains(&restitution),
+            "Restitution must be between 0 and 1"
+        );
+        Elastic { mass, restitution }
+    }
+
+    pub fn mass(self: Elastic) -> units::Mass {
+        self.mass
+    }
+
+    pub fn restitution(self: Elastic) -> units::Scalar {
+        self.restitution
+    }
+}
+
+/// the changes in velocity of two bodies that have just collided,
+/// where normal points from the first body towards the second
+///
+/// the relative velocity changes exactly,
+/// so momentum is conserved to within rounding of the first body's velocity
+pub fn impulse(
+    first: Option<Elastic>,
+    first_velocity: units::Velocity,
+    second: Option<Elastic>,
+    second_velocity: units::Velocity,
+    normal: units::Vector,
+) -> (units::Velocity, units::Velocity) {
+    let none = Default::default();
+    let rel_vel = (first_velocity - second_velocity).as_vector();
+    // already separating
+    if units::Vector::wide_inner(rel_vel, normal) <= units::Scalar::default() {
+        return (none, none);
+    }
+
+    let restitution = match (first, second) {
+        (Some(one), Some(other)) =>
+            cmp::min(one.restitution, other.restitution),
+        (Some(one), None) => one.restitution,
+        (None, Some(other)) => other.restitution,
+        (None, None) => return (none, none),
+    };
+
+    // the change in relative velocity, which cancels the approach and then
+    // reverses some of it
+    let approach = rel_vel.project_onto(normal);
+    let total = approach + approach * restitution;
+
+    // each body takes a share of the change inversely proportional to its
+    // mass, i.e. proportional to the other body's mass
+    let first_change = match (first, second) {
+        (Some(one), Some(other)) => {
+            let sum = (one.mass + other.mass).as_scalar();
+            let share = other.mass.as_scalar();
+            units::Vector {
+                x: -total.x.mul_div(share, sum),
+                y: -total.y.mul_div(share, sum),
+            }
+        },
+        (Some(_), None) => -total,
+        _ => Default::default(),
+    };
+    let second_change = total + first_change;
+
+    (
+        units::Velocity::from_vector(first_change),
+        units::Velocity::from_vector(second_change),
+    )
+}
+
+#[cfg(test)]
+mod test_impulse {
+    use space::testing;
+    use units;
+
+    use super::{impulse, Elastic};
+
+    fn elastic(mass: i32, restitution: units::Scalar) -> Option<Elastic> {
+        let mass = units::Mass::from_scalar(mass.into());
+        Some(Elastic::new(mass, restitution))
+    }
+
+    fn along_x() -> units::Vector {
+        units::Vector { x: 1.into(), y: 0.into() }
+    }
+
+    #[test]
+    fn test_equal_masses_swap() {
+        let changes = impulse(
+            elastic(1, 1.into()),
+            testing::velocity(10, 0),
+            elastic(1, 1.into()),
+            testing::velocity(-10, 0),
+            along_x(),
+        );
+        let expected = (testing::velocity(-20, 0), testing::velocity(20, 0));
+        assert_eq!(changes, expected);
+    }
+
+    #[test]
+    fn test_unequal_masses() {
+        let changes = impulse(
+            elastic(3, 1.into()),
+            testing::velocity(10, 0),
+            elastic(1, 1.into()),
+            testing::velocity(-10, 0),
+            along_x(),
+        );
+        let expected = (testing::velocity(-10, 0), testing::velocity(30, 0));
+        assert_eq!(changes, expected);
+    }
+
+    #[test]
+    fn test_momentum_conserved() {
+        let (first, second) = impulse(
+            elastic(2, units::Scalar::from(1) / 3),
+            testing::velocity(7, -3),
+            elastic(5, 1.into()),
+            testing::velocity(-4, 6),
+            units::Vector { x: 2.into(), y: (-1).into() },
+        );
+        assert!(first != Default::default());
+
+        // only the first change is rounded, and the second is then off by
+        // the same amount, so the total is off by at most the total mass
+        let momentum = first.as_vector() * 2 + second.as_vector() * 5;
+        assert!(momentum.x.into_bits().abs() <= 7);
+        assert!(momentum.y.into_bits().abs() <= 7);
+    }
+
+    #[test]
+    fn test_immovable() {
+        let half = units::Scalar::from(1) / 2;
+        let changes = impulse(
+            elastic(1, half),
+            testing::velocity(10, 0),
+            None,
+            testing::velocity(0, 0),
+            along_x(),
+        );
+        let expected = (testing::velocity(-15, 0), testing::velocity(0, 0));
+        assert_eq!(changes, expected);
+    }
+
+    #[test]
+    fn test_separating() {
+        let changes = impulse(
+            elastic(1, 1.into()),
+            testing::velocity(-10, 0),
+            elastic(1, 1.into()),
+            testing::velocity(10, 0),
+            along_x(),
+        );
+        assert_eq!(changes, Default::default());
+    }
+}
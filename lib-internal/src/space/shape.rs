@@ -0,0 +1,716 @@
+use std::cmp;
+
+use space::quadratic::{self, Trajectory};
+use units;
+
+/// the outline of a body, with any points given relative to its position
+///
+/// only circles can move, everything else is a static wall
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Shape {
+    Circle(units::Distance),
+    Segment(units::Displacement, units::Displacement),
+    /// a convex polygon, with its vertices in order around the edge,
+    /// in either direction
+    Polygon(Vec<units::Displacement>),
+}
+
+impl Shape {
+    /// the box containing this shape when it is at position
+    pub fn bounds(self: &Self, position: units::Position) -> units::Aabb {
+        let centre = position.as_position();
+        match *self {
+            Shape::Circle(radius) => {
+                units::Circle { centre, radius: radius.as_scalar() }.bounds()
+            },
+            Shape::Segment(start, end) => units::Aabb::new(
+                (position + start).as_position(),
+                (position + end).as_position(),
+            ),
+            Shape::Polygon(ref vertices) => {
+                let mut bounds = units::Aabb::from_point(centre);
+                for &vertex in vertices {
+                    let point = (position + vertex).as_position();
+                    bounds = bounds.union(units::Aabb::from_point(point));
+                }
+                bounds
+            },
+        }
+    }
+
+    pub fn radius(self: &Self) -> Option<units::Distance> {
+        match *self {
+            Shape::Circle(radius) => Some(radius),
+            _ => None,
+        }
+    }
+
+    /// the furthest any part of this shape reaches from its position
+    pub fn reach(self: &Self) -> units::Distance {
+        match *self {
+            Shape::Circle(radius) => radius,
+            Shape::Segment(start, end) => {
+                cmp::max(start.magnitude(), end.magnitude())
+            },
+            Shape::Polygon(ref vertices) => vertices
+                .iter()
+                .map(|vertex| vertex.magnitude())
+                .max()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// whether this is a simple convex polygon, with at least three corners,
+    /// every corner turning the same way, and once around in total,
+    /// so that star shapes such as a pentagram aren't convex
+    pub fn is_convex(self: &Self) -> bool {
+        let vertices = match *self {
+            Shape::Polygon(ref vertices) => vertices,
+            _ => return true,
+        };
+        let count = vertices.len();
+        if count < 3 {
+            return false;
+        }
+        let edge = |n: usize| {
+            (vertices[(n + 1) % count] - vertices[n % count]).as_vector()
+        };
+        // whether a direction is in the upper half of the circle,
+        // a change of half is half a turn, since no corner turns that far
+        let upper = |direction: units::Vector| {
+            direction.y > 0 || (direction.y == 0 && direction.x > 0)
+        };
+        let mut left = false;
+        let mut right = false;
+        let mut halves = 0;
+        for n in 0..count {
+            let one = edge(n);
+            let other = edge(n + 1);
+            let turn = units::Vector::cross(one, other);
+            if turn == 0 && units::Vector::inner(one, other) <= 0 {
+                // a repeated vertex, or an edge that doubles back
+                return false;
+            }
+            left |= turn > 0;
+            right |= turn < 0;
+            if upper(one) != upper(other) {
+                halves += 1;
+            }
+        }
+        !(left && right) && halves == 2
+    }
+
+    // the edges of a wall, as pairs of points relative to the wall
+    fn edges(
+        self: &Self,
+    ) -> Vec<(units::Displacement, units::Displacement)> {
+        match *self {
+            Shape::Circle(_) => Vec::new(),
+            Shape::Segment(start, end) => vec![(start, end)],
+            Shape::Polygon(ref vertices) => {
+                let count = vertices.len();
+                (0..count)
+                    .map(|n| (vertices[n], vertices[(n + 1) % count]))
+                    .collect()
+            },
+        }
+    }
+
+    /// the distance from point, relative to this shape, to the nearest part
+    /// of it, which is zero if point is inside it
+    pub fn distance(
+        self: &Self,
+        point: units::Displacement,
+    ) -> units::Distance {
+        match *self {
+            Shape::Circle(radius) => {
+                cmp::max(point.magnitude() - radius, Default::default())
+            },
+            _ => self.wall_distance(point),
+        }
+    }
+
+    /// whether any part of this shape, at position, is inside bounds
+    pub fn overlaps(
+        self: &Self,
+        position: units::Position,
+        bounds: units::Aabb,
+    ) -> bool {
+        if let Shape::Circle(radius) = *self {
+            let centre = position.as_position();
+            let circle = units::Circle { centre, radius: radius.as_scalar() };
+            return circle.intersects_aabb(bounds);
+        }
+        if !self.bounds(position).intersects(bounds) {
+            return false;
+        }
+
+        // both are convex, so if the bounds of the wall overlap the box,
+        // the only lines that could still separate them are its edges
+        let corner = |x, y| {
+            let point = units::Position { x, y };
+            (point - position).as_vector()
+        };
+        let (min, max) = (bounds.min, bounds.max);
+        let corners = [
+            corner(min.x, min.y),
+            corner(max.x, min.y),
+            corner(max.x, max.y),
+            corner(min.x, max.y),
+        ];
+        let edges = self.edges();
+        let vertices: Vec<_> = edges
+            .iter()
+            .flat_map(|&(start, end)| vec![start, end])
+            .map(|vertex| vertex.as_vector())
+            .collect();
+        let separates = |&(start, end): &(_, units::Displacement)| {
+            let edge = (end - start).as_vector();
+            let side = |&point: &units::Vector| {
+                units::Vector::wide_cross(edge, point - start.as_vector())
+            };
+            let (wall_low, wall_high) = range(vertices.iter().map(&side));
+            let (box_low, box_high) = range(corners.iter().map(&side));
+            box_high < wall_low || wall_high < box_low
+        };
+        !edges.iter().any(separates)
+    }
+
+    /// the distance from point to the nearest part of this wall, which is
+    /// zero if point is inside it
+    pub fn wall_distance(
+        self: &Self,
+        point: units::Displacement,
+    ) -> units::Distance {
+        if self.wall_contains(point) {
+            return Default::default();
+        }
+        self.edges()
+            .into_iter()
+            .map(|(start, end)| segment_distance(point, start, end))
+            .min()
+            .expect("Wall without any edges")
+    }
+
+    /// the point on the edge of this wall that is nearest to point
+    pub fn wall_nearest(
+        self: &Self,
+        point: units::Displacement,
+    ) -> units::Displacement {
+        self.edges()
+            .into_iter()
+            .map(|(start, end)| segment_nearest(point, start, end))
+            .min_by_key(|&nearest| (point - nearest).as_vector().wide_squared())
+            .expect("Wall without any edges")
+    }
+
+    /// the direction from this shape at position towards the part of other,
+    /// at other_position, that it is touching
+    ///
+    /// at least one of the shapes should be a circle
+    pub fn normal_towards(
+        self: &Self,
+        position: units::Position,
+        other: &Shape,
+        other_position: units::Position,
+    ) -> units::Vector {
+        match (self.radius(), other.radius()) {
+            (None, Some(_)) => {
+                let rel_pos = other_position - position;
+                (rel_pos - self.wall_nearest(rel_pos)).as_vector()
+            },
+            (Some(_), None) => {
+                let rel_pos = position - other_position;
+                (other.wall_nearest(rel_pos) - rel_pos).as_vector()
+            },
+            _ => (other_position - position).as_vector(),
+        }
+    }
+
+    /// the points on this shape, relative to it, that eyes at eye look
+    /// towards, so that it is in sight if nothing blocks any of them
+    ///
+    /// circles are looked at straight across their middle, and walls at
+    /// their corners and whichever part of them is nearest
+    pub fn sight_points(
+        self: &Self,
+        eye: units::Displacement,
+    ) -> Vec<units::Displacement> {
+        match *self {
+            Shape::Circle(radius) => {
+                let centre = units::Displacement::default();
+                let across = (centre - eye)
+                    .as_vector()
+                    .perp()
+                    .with_length(radius.as_scalar());
+                let across = units::Displacement::from_vector(across);
+                vec![centre, centre + across, centre - across]
+            },
+            Shape::Segment(start, end) => {
+                vec![self.wall_nearest(eye), start, end]
+            },
+            Shape::Polygon(ref vertices) => {
+                let mut points = vec![self.wall_nearest(eye)];
+                points.extend(vertices.iter().cloned());
+                points
+            },
+        }
+    }
+
+    fn wall_contains(self: &Self, point: units::Displacement) -> bool {
+        let vertices = match *self {
+            Shape::Polygon(ref vertices) => vertices,
+            _ => return false,
+        };
+        let mut left = false;
+        let mut right = false;
+        for (start, end) in self.edges() {
+            let edge = (end - start).as_vector();
+            let side = units::Vector::cross(edge, (point - start).as_vector());
+            left |= side > 0;
+            right |= side < 0;
+        }
+        vertices.len() > 2 && !(left && right)
+    }
+
+    /// the durations after which a circle starting at `position`,
+    /// relative to this wall, and moving with `velocity`,
+    /// first touches and then stops touching the wall
+    pub fn wall_contact(
+        self: &Self,
+        position: units::Displacement,
+        velocity: units::Velocity,
+        radius: units::Distance,
+    ) -> Option<(units::Duration, units::Duration)> {
+        // the set of points within radius of a convex wall is also convex,
+        // so the circle is touching the wall over a single interval
+        // this starts and ends on the boundary, which is made of the
+        // capsules around each edge
+        let mut result: Option<(units::Duration, units::Duration)> = None;
+        for (start, end) in self.edges() {
+            let pos = position - start;
+            let capsule = capsule_contact(pos, velocity, end - start, radius);
+            if let Some((contact, release)) = capsule {
+                result = Some(result.map_or((contact, release), |(c, r)| {
+                    (cmp::min(c, contact), cmp::max(r, release))
+                }));
+            }
+        }
+        result
+    }
+
+    /// as in wall_contact, but for a circle with constant acceleration,
+    /// which can touch the wall more than once, between start and end
+    pub fn wall_contacts(
+        self: &Self,
+        trajectory: Trajectory,
+        radius: units::Distance,
+        start: units::Duration,
+        end: units::Duration,
+    ) -> Vec<quadratic::Interval> {
+        let mut result = Vec::new();
+        let mut winding = units::WideScalar::default();
+        for (seg_start, seg_end) in self.edges() {
+            let position = trajectory.position - seg_start;
+            let from_start = Trajectory { position, ..trajectory };
+            let seg = seg_end - seg_start;
+            let capsule =
+                capsule_contacts(from_start, seg, radius, start, end);
+            result = quadratic::union(&result, &capsule);
+            winding = winding + units::Vector::wide_cross(
+                seg_start.as_vector(),
+                seg_end.as_vector(),
+            );
+        }
+
+        // polygons are solid, so the circle can also be deep inside one,
+        // far from every edge, which is whenever it is on the inside of all
+        // of them
+        match *self {
+            Shape::Polygon(ref vertices) if vertices.len() > 2 => (),
+            _ => return result,
+        }
+        let inside = if winding > units::Scalar::default() {
+            (Some(Default::default()), None)
+        } else {
+            (None, Some(Default::default()))
+        };
+        let mut interior = vec![(start, end)];
+        for (seg_start, seg_end) in self.edges() {
+            let position = trajectory.position - seg_start;
+            let from_start = Trajectory { position, ..trajectory };
+            let seg = (seg_end - seg_start).as_vector();
+            let side = |point| units::Vector::wide_cross(seg, point);
+            let edge = from_start.range_contacts(side, inside, start, end);
+            interior = quadratic::intersection(&interior, &edge);
+        }
+        quadratic::union(&result, &interior)
+    }
+
+    /// how far a ray starting at origin, relative to this shape, travels
+    /// in direction before it hits the shape, and the unit normal of the
+    /// surface it hits, which is zero if the ray starts inside the shape
+    ///
+    /// direction should be a unit vector
+    pub fn ray_contact(
+        self: &Self,
+        origin: units::Displacement,
+        direction: units::Vector,
+    ) -> Option<(units::Distance, units::Vector)> {
+        // the ray is a point moving at one unit per unit time, so that
+        // durations and distances are the same number
+        let velocity = units::Velocity::from_vector(direction);
+        let distance = |duration: units::Duration| {
+            units::Distance::from_scalar(duration.as_scalar())
+        };
+        let zero = units::Duration::default();
+
+        if let Shape::Circle(radius) = *self {
+            return match circle_contact(origin, velocity, radius) {
+                Some((_, release)) if release <= zero => None,
+                Some((contact, _)) if contact <= zero => {
+                    Some((Default::default(), Default::default()))
+                },
+                Some((contact, _)) => {
+                    let hit = origin + velocity * contact;
+                    Some((distance(contact), hit.as_vector().normalize()))
+                },
+                None => None,
+            };
+        }
+
+        if self.wall_contains(origin) {
+            return Some((Default::default(), Default::default()));
+        }
+        self.edges()
+            .into_iter()
+            .filter_map(|(start, end)| {
+                let seg = end - start;
+                let pos = origin - start;
+                let radius = Default::default();
+                match capsule_contact(pos, velocity, seg, radius) {
+                    Some((contact, _)) if contact >= zero => {
+                        Some((contact, seg.as_vector()))
+                    },
+                    _ => None,
+                }
+            })
+            .min_by_key(|&(contact, _)| contact)
+            .map(|(contact, seg)| {
+                // either side of the edge, whichever faces the ray
+                let normal = seg.perp().normalize();
+                if units::Vector::inner(normal, direction) > 0 {
+                    (distance(contact), -normal)
+                } else {
+                    (distance(contact), normal)
+                }
+            })
+    }
+}
+
+// the lowest and highest of some values, of which there must be at least one
+fn range<I>(values: I) -> (units::WideScalar, units::WideScalar)
+    where I: Iterator<Item = units::WideScalar>
+{
+    let values: Vec<_> = values.collect();
+    let low = values.iter().cloned().min().expect("Range of nothing");
+    let high = values.iter().cloned().max().expect("Range of nothing");
+    (low, high)
+}
+
+fn segment_distance(
+    point: units::Displacement,
+    start: units::Displacement,
+    end: units::Displacement,
+) -> units::Distance {
+    let seg = (end - start).as_vector();
+    let pos = (point - start).as_vector();
+    let along = units::Vector::wide_inner(seg, pos);
+    if along <= units::Scalar::default() {
+        (point - start).magnitude()
+    } else if along >= seg.wide_squared() {
+        (point - end).magnitude()
+    } else {
+        let length = units::WideScalar::from(seg.magnitude());
+        let across = units::Vector::wide_cross(seg, pos);
+        let dist = if across < units::Scalar::default() {
+            -across / length
+        } else {
+            across / length
+        };
+        units::Distance::from_scalar(dist)
+    }
+}
+
+fn segment_nearest(
+    point: units::Displacement,
+    start: units::Displacement,
+    end: units::Displacement,
+) -> units::Displacement {
+    let seg = (end - start).as_vector();
+    let pos = (point - start).as_vector();
+    let along = units::Vector::wide_inner(seg, pos);
+    if along <= units::Scalar::default() {
+        start
+    } else if along >= seg.wide_squared() {
+        end
+    } else {
+        start + units::Displacement::from_vector(pos.project_onto(seg))
+    }
+}
+
+/// when a point, starting at position and moving with velocity, is within
+/// radius of the origin
+pub fn circle_contact(
+    position: units::Displacement,
+    velocity: units::Velocity,
+    radius: units::Distance,
+) -> Option<(units::Duration, units::Duration)> {
+    let (pos, vel) = (position.as_vector(), velocity.as_vector());
+
+    // squares are kept wide since they overflow for distant bodies,
+    // only the final ratios need to fit in a Scalar
+    let vel_squared = vel.wide_squared();
+
+    // this is the time at which the point will be closest
+    // it follows from assuming (p + vt) is orthogonal to v
+    let inner = units::Vector::wide_inner(pos, vel);
+    let near_time = units::Duration::from_scalar(- inner / vel_squared);
+
+    let coll_squared = radius.as_scalar().wide_squared();
+    let near: units::Displacement = position + velocity * near_time;
+    if coll_squared < near.as_vector().wide_squared() {
+        None
+    } else {
+        // this comes from completing the square in (p + vt)^2 = d^2
+        let diff_squared = (coll_squared - pos.wide_squared())
+                         / vel_squared
+                         + near_time.as_scalar().squared();
+        let diff = units::Duration::from_scalar(diff_squared.sqrt());
+        Some((near_time - diff, near_time + diff))
+    }
+}
+
+// when a point is within radius of the segment from the origin to seg
+fn capsule_contact(
+    position: units::Displacement,
+    velocity: units::Velocity,
+    seg: units::Displacement,
+    radius: units::Distance,
+) -> Option<(units::Duration, units::Duration)> {
+    let (pos, vel, seg_vec) =
+        (position.as_vector(), velocity.as_vector(), seg.as_vector());
+
+    // the rectangle either side of the segment, where the point's
+    // projection lands on the segment, and it is close enough to the line
+    let along = linear_range(
+        units::Vector::wide_inner(seg_vec, pos),
+        units::Vector::wide_inner(seg_vec, vel),
+        Default::default(),
+        seg_vec.wide_squared(),
+    );
+    let width = radius.as_scalar().wide_mul(seg_vec.magnitude());
+    let across = linear_range(
+        units::Vector::wide_cross(seg_vec, pos),
+        units::Vector::wide_cross(seg_vec, vel),
+        -width,
+        width,
+    );
+    let side = match (along, across) {
+        (Some((a, b)), Some((c, d))) if cmp::max(a, c) <= cmp::min(b, d) =>
+            Some((cmp::max(a, c), cmp::min(b, d))),
+        _ => None,
+    };
+
+    // and the circles at either end
+    let start = circle_contact(position, velocity, radius);
+    let end = circle_contact(position - seg, velocity, radius);
+
+    let mut result: Option<(units::Duration, units::Duration)> = None;
+    for part in side.into_iter().chain(start).chain(end) {
+        let (contact, release) = part;
+        result = Some(result.map_or(part, |(c, r)| {
+            (cmp::min(c, contact), cmp::max(r, release))
+        }));
+    }
+    result
+}
+
+// as in capsule_contact, but with constant acceleration
+fn capsule_contacts(
+    trajectory: Trajectory,
+    seg: units::Displacement,
+    radius: units::Distance,
+    start: units::Duration,
+    end: units::Duration,
+) -> Vec<quadratic::Interval> {
+    let seg_vec = seg.as_vector();
+    let along = trajectory.range_contacts(
+        |point| units::Vector::wide_inner(seg_vec, point),
+        (Some(Default::default()), Some(seg_vec.wide_squared())),
+        start,
+        end,
+    );
+    let width = radius.as_scalar().wide_mul(seg_vec.magnitude());
+    let across = trajectory.range_contacts(
+        |point| units::Vector::wide_cross(seg_vec, point),
+        (Some(-width), Some(width)),
+        start,
+        end,
+    );
+    let side = quadratic::intersection(&along, &across);
+
+    let position = trajectory.position - seg;
+    let from_end = Trajectory { position, ..trajectory };
+    let ends = quadratic::union(
+        &trajectory.circle_contacts(radius, start, end),
+        &from_end.circle_contacts(radius, start, end),
+    );
+    quadratic::union(&side, &ends)
+}
+
+// when `start + rate * t` is between low and high,
+// unbounded ends saturate to the most extreme durations
+fn linear_range(
+    start: units::WideScalar,
+    rate: units::WideScalar,
+    low: units::WideScalar,
+    high: units::WideScalar,
+) -> Option<(units::Duration, units::Duration)> {
+    let zero = units::WideScalar::default();
+    if rate == zero {
+        return if low <= start && start <= high {
+            Some((saturated(false), saturated(true)))
+        } else {
+            None
+        };
+    }
+    let one = crossing(low - start, rate);
+    let other = crossing(high - start, rate);
+    Some((cmp::min(one, other), cmp::max(one, other)))
+}
+
+fn crossing(
+    num: units::WideScalar,
+    den: units::WideScalar,
+) -> units::Duration {
+    let zero = units::WideScalar::default();
+    match num.checked_div(den) {
+        Some(time) => units::Duration::from_scalar(time),
+        None => saturated((num < zero) == (den < zero)),
+    }
+}
+
+fn saturated(positive: bool) -> units::Duration {
+    let bits = if positive { i64::MAX } else { i64::MIN };
+    units::Duration::from_scalar(units::Scalar::from_bits(bits))
+}
+
+#[cfg(test)]
+mod test_walls {
+    use space::testing;
+    use units;
+
+    use super::Shape;
+
+    fn seconds(count: i32) -> units::Duration {
+        testing::seconds(count) - units::Time::default()
+    }
+
+    fn post() -> Shape {
+        Shape::Segment(
+            testing::displacement(0, -50),
+            testing::displacement(0, 50),
+        )
+    }
+
+    #[test]
+    fn test_segment_head_on() {
+        let contact = post().wall_contact(
+            testing::displacement(-100, 0),
+            testing::velocity(10, 0),
+            10.into(),
+        );
+        assert_eq!(contact, Some((seconds(9), seconds(11))));
+    }
+
+    #[test]
+    fn test_segment_miss() {
+        let contact = post().wall_contact(
+            testing::displacement(-100, 61),
+            testing::velocity(10, 0),
+            10.into(),
+        );
+        assert_eq!(contact, None);
+    }
+
+    #[test]
+    fn test_capsule_end() {
+        // passes 6 beyond the end, so it only touches the rounded end of the
+        // capsule, while it is within 8 of the end across the segment
+        let contact = post().wall_contact(
+            testing::displacement(-100, 56),
+            testing::velocity(8, 0),
+            10.into(),
+        );
+        let half = units::moments(8);
+        assert_eq!(contact, Some((seconds(11) + half, seconds(13) + half)));
+    }
+
+    #[test]
+    fn test_capsule_along() {
+        // slides along the segment, 6 to one side of it, touching it from
+        // 8 before one end until 8 past the other
+        let contact = post().wall_contact(
+            testing::displacement(6, -100),
+            testing::velocity(0, 8),
+            10.into(),
+        );
+        let quarter = units::moments(4);
+        let expected = (seconds(5) + quarter, seconds(20) - quarter);
+        assert_eq!(contact, Some(expected));
+    }
+
+    #[test]
+    fn test_polygon_distance() {
+        let square = Shape::Polygon(vec![
+            testing::displacement(-10, -10),
+            testing::displacement(10, -10),
+            testing::displacement(10, 10),
+            testing::displacement(-10, 10),
+        ]);
+        let distance = |x, y| {
+            square.wall_distance(testing::displacement(x, y))
+        };
+        assert_eq!(distance(0, 0), 0.into());
+        assert_eq!(distance(30, 0), 20.into());
+        assert_eq!(distance(0, -15), 5.into());
+        assert!(square.is_convex());
+    }
+
+    #[test]
+    fn test_convex() {
+        let polygon = |corners: &[(i32, i32)]| Shape::Polygon(
+            corners
+                .iter()
+                .map(|&(x, y)| testing::displacement(x, y))
+                .collect()
+        );
+        let triangle = [(0, 0), (10, 0), (0, 10)];
+        assert!(polygon(&triangle).is_convex());
+        let mut clockwise = triangle;
+        clockwise.reverse();
+        assert!(polygon(&clockwise).is_convex());
+        // a corner in the middle of an edge is fine
+        assert!(polygon(&[(0, 0), (5, 0), (10, 0), (0, 10)]).is_convex());
+
+        assert!(!polygon(&[]).is_convex());
+        assert!(!polygon(&[(0, 0), (10, 0)]).is_convex());
+        assert!(!polygon(&[(0, 0), (10, 0), (20, 0)]).is_convex());
+        assert!(!polygon(&[(0, 0), (10, 0), (10, 0), (0, 10)]).is_convex());
+        assert!(!polygon(&[(0, 0), (10, 0), (5, 1), (0, 10)]).is_convex());
+        // a pentagram turns the same way at every corner, but twice around
+        let star = [(0, 10), (6, -8), (-10, 3), (10, 3), (-6, -8)];
+        assert!(!polygon(&star).is_convex());
+    }
+}
@@ -0,0 +1,127 @@
+// helpers for tests that set up a space and watch it run
+//
+// players report everything that happens through their update channels, so
+// tests watch those rather than reaching into the world once it is running
+
+use std::sync::mpsc;
+
+use entities::{self, player, wall};
+use space;
+use sulphate;
+use units;
+
+pub struct Scene {
+    pub space: space::CollisionSpace,
+    pub time: sulphate::EventQueue,
+    pub matter: sulphate::EntityHeap,
+}
+
+impl Scene {
+    pub fn new() -> Scene {
+        let space = space::CollisionSpace::new();
+        let time = sulphate::EventQueue::new(Default::default());
+        let matter = sulphate::EntityHeap::with_seed(0);
+        Scene { space, time, matter }
+    }
+
+    /// adds a player with a radius of 10 at position, moving with velocity,
+    /// after setup, and returns its id and whatever it will be told
+    pub fn player<F>(
+        self: &mut Self,
+        position: units::Position,
+        velocity: units::Velocity,
+        setup: F,
+    ) -> (sulphate::EntityId, mpsc::Receiver<player::Update>)
+        where F: FnOnce(&mut player::Player)
+    {
+        let (send, recv) = mpsc::channel();
+        let mut this = player::Player::new(
+            &mut self.space,
+            &mut self.time,
+            &mut self.matter,
+            position,
+            10.into(),
+            send,
+        );
+        let now = this.now();
+        this.body = Some(space::Body::new(position, velocity, now));
+        setup(this.get_mut().unwrap());
+        (this.id(), recv)
+    }
+
+    pub fn wall<F>(
+        self: &mut Self,
+        position: units::Position,
+        shape: space::Shape,
+        setup: F,
+    ) -> sulphate::EntityId
+        where F: FnOnce(&mut wall::Wall)
+    {
+        let mut this = wall::Wall::new(
+            &mut self.space,
+            &mut self.time,
+            &mut self.matter,
+            position,
+            shape,
+        );
+        setup(this.get_mut().unwrap());
+        this.id()
+    }
+
+    /// runs everything that happens up to until
+    pub fn run(self: Self, until: units::Time) {
+        let Scene { space, mut time, matter } = self;
+        let mut world = sulphate::World::new(space, matter);
+        time.simulate(&mut world, until);
+    }
+}
+
+pub fn position(x: i32, y: i32) -> units::Position {
+    units::Position::default() + displacement(x, y)
+}
+
+pub fn displacement(x: i32, y: i32) -> units::Displacement {
+    units::Displacement { x: x.into(), y: y.into() }
+}
+
+pub fn velocity(x: i32, y: i32) -> units::Velocity {
+    units::Velocity { x: x.into(), y: y.into() }
+}
+
+pub fn seconds(count: i32) -> units::Time {
+    let moments = count * units::MOMENT_RATE as i32;
+    units::Time::default() + units::moments(moments)
+}
+
+/// everything a player has been told it can see, before and after each
+/// change, and when
+pub fn vision_updates(
+    recv: &mpsc::Receiver<player::Update>,
+) -> Vec<(units::Time, Option<space::Image>, Option<space::Image>)> {
+    recv.try_iter()
+        .filter_map(|update| match update.what {
+            player::UpdateData::Vision { before, after } => {
+                Some((update.when, before, after))
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// every image of a player that a player has been told about, and when,
+/// which are all of itself if it is the only player
+pub fn player_images(
+    recv: &mpsc::Receiver<player::Update>,
+) -> Vec<(units::Time, space::Image)> {
+    recv.try_iter()
+        .filter_map(|update| match update.what {
+            player::UpdateData::Vision { after: Some(after), .. } => {
+                match after.inner_image {
+                    entities::Image::Player(_) => Some((update.when, after)),
+                    _ => None,
+                }
+            },
+            _ => None,
+        })
+        .collect()
+}
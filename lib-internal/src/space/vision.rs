@@ -0,0 +1,1016 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use space;
+use space::body;
+use space::eyes;
+use sulphate;
+use units;
+
+// eyes are only told about the bodies in sight of them, which the space keeps
+// track of, and are told when bodies come into or go out of sight, at times
+// found the same way as collision times
+//
+// an eye is a circle around the centre of whoever is looking, as big as their
+// vision, and whatever it touches is in range
+//
+// like marching, eyes only look at what the grid lists near them, which is
+// only good until the next horizon, see body::index_horizon. so a change is
+// only shown to eyes that could reach it before then, and moving bodies look
+// around again every horizon, in case they have come near anything new
+//
+// whatever is in range is in sight unless opaque bodies block every line from
+// the centre of the eye to the points the eye looks at, see
+// Shape::sight_points. these lines only change when something near them
+// changes, so whenever an opaque body changes, eyes look again at whatever
+// it might have come in front of or out from in front of. while the eyes,
+// the target, or an opaque body is moving, and the opaque body might cross
+// the lines between them before the next horizon, the eyes watch the
+// target, glancing at it once a moment. eyes that see everything look again
+// at every moving body each horizon while anything is opaque, in case it is
+// heading for the lines to or from anything opaque
+
+/// what is within range of a pair of eyes
+pub enum Sight {
+    Everything,
+    Only(BTreeSet<sulphate::EntityUId>),
+}
+
+/// how far every pair of eyes can see
+pub struct Viewers {
+    limited: HashMap<sulphate::EntityUId, units::Distance>,
+    // how many eyes see exactly so far, so that the furthest is at hand
+    distances: BTreeMap<units::Distance, usize>,
+    unlimited: BTreeSet<sulphate::EntityUId>,
+}
+
+impl Viewers {
+    pub fn new() -> Viewers {
+        let limited = HashMap::new();
+        let distances = BTreeMap::new();
+        let unlimited = BTreeSet::new();
+        Viewers { limited, distances, unlimited }
+    }
+
+    fn insert(
+        self: &mut Self,
+        uid: sulphate::EntityUId,
+        vision: Option<units::Distance>,
+    ) {
+        match vision {
+            Some(distance) => {
+                self.limited.insert(uid, distance);
+                *self.distances.entry(distance).or_insert(0) += 1;
+            },
+            None => {
+                self.unlimited.insert(uid);
+            },
+        }
+    }
+
+    fn remove(self: &mut Self, uid: sulphate::EntityUId) {
+        self.unlimited.remove(&uid);
+        let distance = match self.limited.remove(&uid) {
+            Some(distance) => distance,
+            None => return,
+        };
+        let now_none = {
+            let count = self.distances.get_mut(&distance).expect(
+                "Eyes that see a distance nobody else was counted at"
+            );
+            *count -= 1;
+            *count == 0
+        };
+        if now_none {
+            self.distances.remove(&distance);
+        }
+    }
+
+    // the furthest that any eyes with limited vision can see
+    fn reach(self: &Self) -> Option<units::Distance> {
+        self.distances.keys().next_back().cloned()
+    }
+}
+
+/// tells everything with eyes that can see uid that it has changed from
+/// before to after, and if uid has eyes, tells it about whatever has come
+/// into or gone out of its sight
+pub fn changed(
+    space: &mut space::CollisionSpace,
+    time: &mut sulphate::EventQueue,
+    matter: &mut sulphate::EntityHeap,
+    uid: sulphate::EntityUId,
+    before: Option<&space::Image>,
+    after: Option<&space::Image>,
+) {
+    let now = time.now();
+    let has_eyes = eyes::as_eyes(uid).is_some();
+    if has_eyes {
+        // everyone can always see themselves
+        show(space, time, matter, uid, before, after);
+
+        space.viewers.remove(uid);
+        if let Some(after) = after {
+            space.viewers.insert(uid, after.inner_image.vision());
+        }
+    }
+
+    let mut viewers = space.viewers.unlimited.clone();
+    for image in before.into_iter().chain(after) {
+        viewers.extend(viewers_near(space, image, now));
+    }
+    viewers.remove(&uid);
+    for viewer in viewers {
+        look(space, time, matter, viewer, uid, (before, after));
+    }
+
+    let in_the_way = before.into_iter().chain(after);
+    for image in in_the_way.filter(|image| image.inner_image.opaque()) {
+        glance_across(space, time, matter, uid, image, now);
+    }
+
+    let after = match after {
+        Some(after) => after,
+        None => {
+            space.in_view.remove(&uid);
+            space.hidden.remove(&uid);
+            return;
+        },
+    };
+    let moved = match before {
+        Some(before) => before.body != after.body,
+        None => true,
+    };
+    if moved && after.body.is_moving() {
+        let event = ScanEvent { uid, body: after.body.clone() };
+        sulphate::enqueue_relative(time, event, body::index_horizon());
+    }
+    if !has_eyes || !view_changed(before, after) {
+        return;
+    }
+
+    // eyes that see everything don't list it, so list it before looking
+    // at which parts they can still see
+    let vision = after.inner_image.vision();
+    let targets: Vec<sulphate::EntityUId> =
+        match (vision, space.in_view.get(&uid)) {
+            (Some(distance), Some(Sight::Only(in_range))) => {
+                targets_near(space, uid, after, distance, now)
+                    .union(in_range)
+                    .cloned()
+                    .collect()
+            },
+            (Some(distance), None) => {
+                targets_near(space, uid, after, distance, now)
+                    .into_iter()
+                    .collect()
+            },
+            _ => {
+                space.contents
+                     .values()
+                     .cloned()
+                     .filter(|&target| target != uid)
+                     .collect()
+            },
+        };
+    if let (Some(_), Some(&Sight::Everything)) =
+        (vision, space.in_view.get(&uid))
+    {
+        let listed = targets.iter().cloned().collect();
+        space.in_view.insert(uid, Sight::Only(listed));
+    }
+    for target in targets {
+        let image = space.get_uid_image(matter, target);
+        let image = image.as_ref();
+        look(space, time, matter, uid, target, (image, image));
+    }
+    if vision.is_none() {
+        space.in_view.insert(uid, Sight::Everything);
+    }
+}
+
+// the eyes with limited vision that might see image at some point before the
+// next horizon
+fn viewers_near(
+    space: &space::CollisionSpace,
+    image: &space::Image,
+    now: units::Time,
+) -> BTreeSet<sulphate::EntityUId> {
+    let reach = match space.viewers.reach() {
+        Some(reach) => reach,
+        None => return BTreeSet::new(),
+    };
+    let bounds = image_bounds(image, now).expand(reach.as_scalar());
+    space.grid
+         .query(bounds)
+         .into_iter()
+         .filter(|uid| space.viewers.limited.contains_key(uid))
+         .collect()
+}
+
+// whatever viewer might see from view, as far as distance, at some point
+// before the next horizon
+fn targets_near(
+    space: &space::CollisionSpace,
+    viewer: sulphate::EntityUId,
+    view: &space::Image,
+    distance: units::Distance,
+    now: units::Time,
+) -> BTreeSet<sulphate::EntityUId> {
+    let bounds = image_bounds(view, now).expand(distance.as_scalar());
+    space.grid
+         .query(bounds)
+         .into_iter()
+         .filter(|&target| target != viewer)
+         .collect()
+}
+
+// looks again at whatever eyes might see across the path of an opaque body
+// between now and the next horizon
+fn glance_across(
+    space: &mut space::CollisionSpace,
+    time: &mut sulphate::EventQueue,
+    matter: &mut sulphate::EntityHeap,
+    uid: sulphate::EntityUId,
+    image: &space::Image,
+    now: units::Time,
+) {
+    let bounds = image_bounds(image, now);
+    let end = now + body::index_horizon();
+    let mut viewers = space.viewers.unlimited.clone();
+    viewers.extend(viewers_near(space, image, now));
+    viewers.remove(&uid);
+    for viewer in viewers {
+        let view = match space.get_uid(viewer) {
+            Some(c_body) => c_body.body.clone(),
+            None => continue,
+        };
+        let targets: Vec<sulphate::EntityUId> =
+            match (space.in_view.get(&viewer), space.grid.extent()) {
+                (Some(&Sight::Everything), Some(extent)) => {
+                    let eye = view.swept_bounds(Default::default(), now, end);
+                    space.grid
+                         .query(shadow_bounds(eye, bounds, extent))
+                         .into_iter()
+                         .filter(|&target| target != viewer)
+                         .collect()
+                },
+                (Some(Sight::Only(targets)), _) => {
+                    targets.iter().cloned().collect()
+                },
+                _ => Vec::new(),
+            };
+        for target in targets {
+            let across = match space.get_uid(target) {
+                Some(c_body) if target != uid => {
+                    let (body, shape) = (&c_body.body, &c_body.shape);
+                    sight_bounds(&view, body, shape, now, end)
+                        .intersects(bounds)
+                },
+                _ => false,
+            };
+            if across {
+                let image = space.get_uid_image(matter, target);
+                let image = image.as_ref();
+                look(space, time, matter, viewer, target, (image, image));
+            }
+        }
+    }
+}
+
+// the part of extent where a target might be, for the box containing the
+// lines from eye to it to meet blocker, which is everything on the far side
+// of blocker from eye, along each axis that eye is to one side of it
+fn shadow_bounds(
+    eye: units::Aabb,
+    blocker: units::Aabb,
+    extent: units::Aabb,
+) -> units::Aabb {
+    let mut shadow = extent.union(blocker);
+    if eye.max.x < blocker.min.x {
+        shadow.min.x = blocker.min.x;
+    }
+    if blocker.max.x < eye.min.x {
+        shadow.max.x = blocker.max.x;
+    }
+    if eye.max.y < blocker.min.y {
+        shadow.min.y = blocker.min.y;
+    }
+    if blocker.max.y < eye.min.y {
+        shadow.max.y = blocker.max.y;
+    }
+    shadow
+}
+
+// the box containing image from now until the next horizon
+fn image_bounds(image: &space::Image, now: units::Time) -> units::Aabb {
+    let shape = image.inner_image.shape();
+    let end = now + body::index_horizon();
+    body::swept_bounds(&image.body, &shape, now, end)
+}
+
+// whether a viewer that has changed from before to after might see anything
+// differently
+fn view_changed(before: Option<&space::Image>, after: &space::Image) -> bool {
+    let vision = after.inner_image.vision();
+    match before {
+        Some(before) if before.inner_image.vision() == vision => {
+            vision.is_some() && before.body != after.body
+        },
+        _ => true,
+    }
+}
+
+// tells viewer that target has changed from before to after, as far as it
+// could see before and can see after, and arranges to look again whenever
+// it next comes into or goes out of range
+fn look(
+    space: &mut space::CollisionSpace,
+    time: &mut sulphate::EventQueue,
+    matter: &mut sulphate::EntityHeap,
+    viewer: sulphate::EntityUId,
+    target: sulphate::EntityUId,
+    (before, after): (Option<&space::Image>, Option<&space::Image>),
+) {
+    let was_in_range = in_range(space, viewer, target);
+    let was_seen = was_in_range && !is_hidden(space, viewer, target);
+    let now = time.now();
+    let view = space.get_uid_image(matter, viewer);
+    let pair = (viewer, target);
+    let (in_range, hidden, watch, next) = match (view, after) {
+        (Some(view), Some(after)) => {
+            let (in_range, next) = in_reach(&view, after, now);
+            let hidden = in_range && blocked(space, pair, &view, after, now);
+            let watch =
+                in_range && might_change(space, pair, &view, after, now);
+            (in_range, hidden, watch, next)
+        },
+        _ => (false, false, false, None),
+    };
+    let seen = in_range && !hidden;
+
+    set_in_range(space, viewer, target, in_range);
+    set_hidden(space, viewer, target, hidden);
+    set_watching(space, time, pair, watch);
+    if let Some(next) = next {
+        look_again(space, time, viewer, target, next);
+    }
+
+    let shown_before = if was_seen { before } else { None };
+    let shown_after = if seen { after } else { None };
+    if shown_before != shown_after {
+        show(space, time, matter, viewer, shown_before, shown_after);
+    }
+}
+
+// arranges for viewer to look at target again at time, unless it already
+// will by then. a look arranged for later still happens, but finds that it
+// has been replaced, see VisionEvent
+fn look_again(
+    space: &mut space::CollisionSpace,
+    time: &mut sulphate::EventQueue,
+    viewer: sulphate::EntityUId,
+    target: sulphate::EntityUId,
+    at: units::Time,
+) {
+    let pair = (viewer, target);
+    if let Some(&pending) = space.pending.get(&pair) {
+        if pending <= at {
+            return;
+        }
+    }
+    space.pending.insert(pair, at);
+    sulphate::enqueue_absolute(time, VisionEvent { viewer, target }, at);
+}
+
+// whether target is in range of view at time, and when that next changes
+fn in_reach(
+    view: &space::Image,
+    target: &space::Image,
+    time: units::Time,
+) -> (bool, Option<units::Time>) {
+    let radius = match view.inner_image.vision() {
+        Some(radius) => radius,
+        None => return (true, None),
+    };
+    let eye = space::Shape::Circle(radius);
+    let shape = target.inner_image.shape();
+    let impact = body::shape_impact(
+        (&view.body, &eye),
+        (&target.body, &shape),
+        Default::default(),
+        time,
+    );
+    match impact {
+        Some(impact) if impact.contact == time => (true, impact.release),
+        Some(impact) => (false, Some(impact.contact)),
+        None => (false, None),
+    }
+}
+
+// whether opaque bodies, besides the viewer and target themselves, block
+// every line from the centre of view to the sight points of target at time
+fn blocked(
+    space: &space::CollisionSpace,
+    (viewer, target): (sulphate::EntityUId, sulphate::EntityUId),
+    view: &space::Image,
+    target_image: &space::Image,
+    time: units::Time,
+) -> bool {
+    if space.opaque == 0 {
+        return false;
+    }
+    let eye = view.body.position(time);
+    let position = target_image.body.position(time);
+    let shape = target_image.inner_image.shape();
+    let points = shape.sight_points(eye - position);
+    let clear = |point: units::Displacement| {
+        let point = position + point;
+        let line = (point - eye).as_vector();
+        let length = units::Distance::from_scalar(line.magnitude());
+        let direction = line.normalize();
+        if direction == units::Vector::default() {
+            return true;
+        }
+        let bounds =
+            units::Aabb::new(eye.as_position(), point.as_position());
+        !space.grid
+            .query(bounds)
+            .into_iter()
+            .filter(|&uid| uid != viewer && uid != target)
+            .flat_map(|uid| space.find_uid(uid))
+            .any(|n| {
+                let (_, c_body) = space.at(n);
+                if !c_body.opaque {
+                    return false;
+                }
+                let origin = eye - c_body.body.position(time);
+                match c_body.shape.ray_contact(origin, direction) {
+                    Some((distance, _)) => distance < length,
+                    None => false,
+                }
+            })
+    };
+    !points.into_iter().any(clear)
+}
+
+// whether any opaque body, besides the viewer and target themselves, might
+// get in the way of view seeing target, or take itself out of the way,
+// before the next horizon, because it or the viewer or the target is moving
+// and the box it sweeps through meets the box their lines sweep through
+fn might_change(
+    space: &space::CollisionSpace,
+    (viewer, target): (sulphate::EntityUId, sulphate::EntityUId),
+    view: &space::Image,
+    target_image: &space::Image,
+    time: units::Time,
+) -> bool {
+    if space.opaque == 0 {
+        return false;
+    }
+    let end = time + body::index_horizon();
+    let shape = target_image.inner_image.shape();
+    let bounds =
+        sight_bounds(&view.body, &target_image.body, &shape, time, end);
+    let moving = view.body.is_moving() || target_image.body.is_moving();
+    space.grid
+         .query(bounds)
+         .into_iter()
+         .filter(|&uid| uid != viewer && uid != target)
+         .any(|uid| match space.get_uid(uid) {
+             Some(c_body) if c_body.opaque => {
+                 let (body, shape) = (&c_body.body, &c_body.shape);
+                 (moving || body.is_moving())
+                     && body::swept_bounds(body, shape, time, end)
+                         .intersects(bounds)
+             },
+             _ => false,
+         })
+}
+
+// the box containing every line from the centre of view to a target body of
+// the given shape, from start until end
+fn sight_bounds(
+    view: &space::Body,
+    target: &space::Body,
+    shape: &space::Shape,
+    start: units::Time,
+    end: units::Time,
+) -> units::Aabb {
+    let eye = view.swept_bounds(Default::default(), start, end);
+    body::swept_bounds(target, shape, start, end).union(eye)
+}
+
+fn show(
+    space: &mut space::CollisionSpace,
+    time: &mut sulphate::EventQueue,
+    matter: &mut sulphate::EntityHeap,
+    viewer: sulphate::EntityUId,
+    before: Option<&space::Image>,
+    after: Option<&space::Image>,
+) {
+    if let Some(id) = eyes::as_eyes(viewer) {
+        eyes::update(id, space, time, matter, before, after);
+    }
+}
+
+fn in_range(
+    space: &space::CollisionSpace,
+    viewer: sulphate::EntityUId,
+    target: sulphate::EntityUId,
+) -> bool {
+    match space.in_view.get(&viewer) {
+        Some(&Sight::Everything) => true,
+        Some(Sight::Only(targets)) => targets.contains(&target),
+        None => false,
+    }
+}
+
+fn set_in_range(
+    space: &mut space::CollisionSpace,
+    viewer: sulphate::EntityUId,
+    target: sulphate::EntityUId,
+    in_range: bool,
+) {
+    if in_range {
+        let sight = space.in_view
+            .entry(viewer)
+            .or_insert_with(|| Sight::Only(BTreeSet::new()));
+        if let Sight::Only(ref mut targets) = *sight {
+            targets.insert(target);
+        }
+        return;
+    }
+    let now_empty = match space.in_view.get_mut(&viewer) {
+        Some(&mut Sight::Only(ref mut targets)) => {
+            targets.remove(&target);
+            targets.is_empty()
+        },
+        _ => false,
+    };
+    if now_empty {
+        space.in_view.remove(&viewer);
+    }
+}
+
+fn is_hidden(
+    space: &space::CollisionSpace,
+    viewer: sulphate::EntityUId,
+    target: sulphate::EntityUId,
+) -> bool {
+    match space.hidden.get(&viewer) {
+        Some(targets) => targets.contains(&target),
+        None => false,
+    }
+}
+
+fn set_hidden(
+    space: &mut space::CollisionSpace,
+    viewer: sulphate::EntityUId,
+    target: sulphate::EntityUId,
+    hidden: bool,
+) {
+    if hidden {
+        space.hidden.entry(viewer).or_default().insert(target);
+        return;
+    }
+    let now_empty = match space.hidden.get_mut(&viewer) {
+        Some(targets) => {
+            targets.remove(&target);
+            targets.is_empty()
+        },
+        None => false,
+    };
+    if now_empty {
+        space.hidden.remove(&viewer);
+    }
+}
+
+// glances at target once a moment while watch is true, see GlanceEvent
+fn set_watching(
+    space: &mut space::CollisionSpace,
+    time: &mut sulphate::EventQueue,
+    pair: (sulphate::EntityUId, sulphate::EntityUId),
+    watch: bool,
+) {
+    if !watch {
+        space.watching.remove(&pair);
+        return;
+    }
+    space.watching.insert(pair);
+    if !space.glancing {
+        space.glancing = true;
+        sulphate::enqueue_relative(time, GlanceEvent, units::moments(1));
+    }
+}
+
+// looks again at target, once it might have come into or gone out of sight,
+// unless a sooner look has been arranged since
+struct VisionEvent {
+    viewer: sulphate::EntityUId,
+    target: sulphate::EntityUId,
+}
+
+impl sulphate::Event for VisionEvent {
+    fn invoke(
+        self: Self,
+        space: &mut space::CollisionSpace,
+        time: &mut sulphate::EventQueue,
+        matter: &mut sulphate::EntityHeap,
+    ) {
+        let pair = (self.viewer, self.target);
+        if space.pending.get(&pair) != Some(&time.now()) {
+            return;
+        }
+        space.pending.remove(&pair);
+
+        let image = space.get_uid_image(matter, self.target);
+        let image = image.as_ref();
+        look(space, time, matter, self.viewer, self.target, (image, image));
+    }
+}
+
+// looks around again for a body still moving the same way, once the grid can
+// list it near anything new
+struct ScanEvent {
+    uid: sulphate::EntityUId,
+    body: space::Body,
+}
+
+impl sulphate::Event for ScanEvent {
+    fn invoke(
+        self: Self,
+        space: &mut space::CollisionSpace,
+        time: &mut sulphate::EventQueue,
+        matter: &mut sulphate::EntityHeap,
+    ) {
+        let image = match space.get_uid_image(matter, self.uid) {
+            Some(image) => image,
+            None => return,
+        };
+        if image.body != self.body {
+            return;  // it has changed since, and looks around from there
+        }
+
+        // eyes that see everything only need to look again in case it is
+        // heading into or out of the way of something opaque
+        let now = time.now();
+        let seen = Some(&image);
+        let mut viewers = viewers_near(space, &image, now);
+        if space.opaque > 0 {
+            viewers.extend(space.viewers.unlimited.iter().cloned());
+        }
+        for viewer in viewers {
+            if viewer != self.uid {
+                look(space, time, matter, viewer, self.uid, (seen, seen));
+            }
+        }
+        let vision = space.viewers.limited.get(&self.uid).cloned();
+        let targets = match vision {
+            Some(distance) => {
+                targets_near(space, self.uid, &image, distance, now)
+            },
+            None if space.opaque > 0
+                && space.viewers.unlimited.contains(&self.uid) =>
+            {
+                space.contents
+                     .values()
+                     .cloned()
+                     .filter(|&target| target != self.uid)
+                     .collect()
+            },
+            None => BTreeSet::new(),
+        };
+        for target in targets {
+            let image = space.get_uid_image(matter, target);
+            let image = image.as_ref();
+            look(space, time, matter, self.uid, target, (image, image));
+        }
+        if image.inner_image.opaque() {
+            glance_across(space, time, matter, self.uid, &image, now);
+        }
+        sulphate::enqueue_relative(time, self, body::index_horizon());
+    }
+}
+
+// every pair of eyes looks again at whatever it is watching, to see whether
+// it has come out from or gone behind anything opaque, and keeps doing so
+// once a moment while it is watching anything
+struct GlanceEvent;
+
+impl sulphate::Event for GlanceEvent {
+    fn invoke(
+        self: Self,
+        space: &mut space::CollisionSpace,
+        time: &mut sulphate::EventQueue,
+        matter: &mut sulphate::EntityHeap,
+    ) {
+        let pairs: Vec<_> = space.watching.iter().cloned().collect();
+        for (viewer, target) in pairs {
+            let image = space.get_uid_image(matter, target);
+            let image = image.as_ref();
+            look(space, time, matter, viewer, target, (image, image));
+        }
+
+        if space.watching.is_empty() {
+            space.glancing = false;
+        } else {
+            sulphate::enqueue_relative(time, GlanceEvent, units::moments(1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_vision {
+    use std::sync::mpsc;
+
+    use entities::{self, player, wall};
+    use space;
+    use space::testing::{self, Scene};
+    use sulphate;
+    use units;
+
+    struct SetOpaque {
+        id: sulphate::EntityId,
+        opaque: bool,
+    }
+
+    impl sulphate::Event for SetOpaque {
+        fn invoke(
+            self: Self,
+            space: &mut space::CollisionSpace,
+            time: &mut sulphate::EventQueue,
+            matter: &mut sulphate::EntityHeap,
+        ) {
+            let mut this = space.entry::<wall::Wall>(time, matter, self.id);
+            this.get_mut().unwrap().set_opaque(self.opaque);
+        }
+    }
+
+    struct SetVelocity {
+        id: sulphate::EntityId,
+        velocity: units::Velocity,
+    }
+
+    impl sulphate::Event for SetVelocity {
+        fn invoke(
+            self: Self,
+            space: &mut space::CollisionSpace,
+            time: &mut sulphate::EventQueue,
+            matter: &mut sulphate::EntityHeap,
+        ) {
+            let mut this = space.entry::<player::Player>(time, matter, self.id);
+            let now = this.now();
+            let body = this.body.take().unwrap();
+            this.body = Some(body.split(self.velocity, now));
+        }
+    }
+
+    // reports how many pairs of eyes and targets are being watched
+    struct CountWatching {
+        report: mpsc::Sender<usize>,
+    }
+
+    impl sulphate::Event for CountWatching {
+        fn invoke(
+            self: Self,
+            space: &mut space::CollisionSpace,
+            _time: &mut sulphate::EventQueue,
+            _matter: &mut sulphate::EntityHeap,
+        ) {
+            self.report.send(space.watching.len()).unwrap();
+        }
+    }
+
+    // marks the target, as the only player in this category
+    fn target(player: &mut player::Player) {
+        player.set_filter(space::Filter::new(space::Filter::PICKUPS))
+    }
+
+    // when the viewer saw the target come into sight or go out of it
+    fn sightings(
+        recv: &mpsc::Receiver<player::Update>,
+    ) -> Vec<(units::Time, bool)> {
+        let is_target = |image: &Option<space::Image>| match *image {
+            Some(space::Image {
+                inner_image: entities::Image::Player(ref player),
+                ..
+            }) => player.filter.category == space::Filter::PICKUPS,
+            _ => false,
+        };
+        testing::vision_updates(recv)
+            .into_iter()
+            .filter_map(|(when, before, after)| {
+                match (is_target(&before), is_target(&after)) {
+                    (false, true) => Some((when, true)),
+                    (true, false) => Some((when, false)),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_opaque_toggled() {
+        let mut scene = Scene::new();
+        let still = testing::velocity(0, 0);
+        let (_, recv) = scene.player(testing::position(0, 0), still, |_| ());
+        let shape = space::Shape::Segment(
+            testing::displacement(0, -50),
+            testing::displacement(0, 50),
+        );
+        let id = scene.wall(testing::position(50, 0), shape, |_| ());
+        scene.player(testing::position(100, 0), still, target);
+
+        for &(second, opaque) in &[(1, true), (2, false)] {
+            let toggle = SetOpaque { id, opaque };
+            let when = testing::seconds(second);
+            sulphate::enqueue_absolute(&mut scene.time, toggle, when);
+        }
+        scene.run(testing::seconds(3));
+
+        let expected = vec![
+            (testing::seconds(0), true),
+            (testing::seconds(1), false),
+            (testing::seconds(2), true),
+        ];
+        assert_eq!(sightings(&recv), expected);
+    }
+
+    #[test]
+    fn test_moving_opaque() {
+        // crosses the lines to the edges of the target from 10.56s until
+        // 11.67s, and is only glanced at once a moment
+        let mut scene = Scene::new();
+        let still = testing::velocity(0, 0);
+        let (_, recv) = scene.player(testing::position(0, 0), still, |_| ());
+        scene.player(testing::position(200, 0), still, target);
+        let start = testing::position(100, -100);
+        let velocity = testing::velocity(0, 9);
+        scene.player(start, velocity, |blocker| blocker.set_opaque(true));
+        scene.run(testing::seconds(20));
+
+        let moment = |count| testing::seconds(10) + units::moments(count);
+        let expected = vec![
+            (testing::seconds(0), true),
+            (moment(9), false),
+            (moment(27), true),
+        ];
+        assert_eq!(sightings(&recv), expected);
+    }
+
+    #[test]
+    fn test_far_opaque_unwatched() {
+        let mut scene = Scene::new();
+        let start = testing::position(0, 0);
+        scene.player(start, testing::velocity(10, 0), |_| ());
+        let start = testing::position(200, 0);
+        scene.player(start, testing::velocity(0, 10), target);
+        let shape = space::Shape::Segment(
+            testing::displacement(0, -50),
+            testing::displacement(0, 50),
+        );
+        scene.wall(testing::position(2000, 2000), shape, |wall| {
+            wall.set_opaque(true)
+        });
+
+        let (report, counts) = mpsc::channel();
+        for &second in &[1, 5, 10] {
+            let count = CountWatching { report: report.clone() };
+            let when = testing::seconds(second);
+            sulphate::enqueue_absolute(&mut scene.time, count, when);
+        }
+        scene.run(testing::seconds(11));
+        assert_eq!(counts.try_iter().collect::<Vec<_>>(), vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn test_passes_behind_still_opaque() {
+        // the target only comes near the lines past the wall after a few
+        // horizons, and the eyes see it go behind the wall and come out
+        // again at the glances just after it does
+        let mut scene = Scene::new();
+        let still = testing::velocity(0, 0);
+        let (_, recv) = scene.player(testing::position(0, 0), still, |_| ());
+        let start = testing::position(300, -100);
+        scene.player(start, testing::velocity(0, 20), target);
+        let shape = space::Shape::Segment(
+            testing::displacement(-20, 0),
+            testing::displacement(20, 0),
+        );
+        scene.wall(testing::position(150, 150), shape, |wall| {
+            wall.set_opaque(true)
+        });
+        scene.run(testing::seconds(30));
+
+        let expected = vec![
+            (testing::seconds(0), true),
+            (testing::seconds(18) + units::moments(15), false),
+            (testing::seconds(21) + units::moments(9), true),
+        ];
+        assert_eq!(sightings(&recv), expected);
+    }
+
+    #[test]
+    fn test_shadow_bounds() {
+        let aabb = |min: (i32, i32), max: (i32, i32)| units::Aabb::new(
+            testing::position(min.0, min.1).as_position(),
+            testing::position(max.0, max.1).as_position(),
+        );
+        let extent = aabb((-1000, -1000), (1000, 1000));
+        let eye = aabb((0, 0), (0, 0));
+
+        // everything beyond it, on both axes, or on the one the eye is
+        // to one side of
+        let beside = aabb((100, 50), (120, 60));
+        let shadow = super::shadow_bounds(eye, beside, extent);
+        assert_eq!(shadow, aabb((100, 50), (1000, 1000)));
+        let ahead = aabb((-10, -80), (10, -70));
+        let shadow = super::shadow_bounds(eye, ahead, extent);
+        assert_eq!(shadow, aabb((-1000, -1000), (1000, -70)));
+    }
+
+    fn vision(distance: i32) -> Option<units::Distance> {
+        Some(distance.into())
+    }
+
+    // a target passing by a viewer that can see 100 away, from far enough
+    // away that it has to be found as it comes
+    #[test]
+    fn test_enter_and_leave() {
+        let mut scene = Scene::new();
+        let still = testing::velocity(0, 0);
+        let (_, recv) = scene.player(testing::position(0, 0), still, |p| {
+            p.set_vision(vision(100))
+        });
+        let start = testing::position(300, 66);
+        scene.player(start, testing::velocity(-16, 0), target);
+        scene.run(testing::seconds(60));
+
+        // in range while its edge is within 100 of the viewer's centre, so
+        // from 88 away along its path on one side to 88 away on the other,
+        // and only out of range the instant after it reaches the far edge
+        let quarter = units::moments(4);
+        let instant = units::Duration::from_scalar(units::Scalar::from_bits(1));
+        let expected = vec![
+            (testing::seconds(13) + quarter, true),
+            (testing::seconds(24) + quarter + instant, false),
+        ];
+        assert_eq!(sightings(&recv), expected);
+    }
+
+    #[test]
+    fn test_viewer_approaches() {
+        let mut scene = Scene::new();
+        let start = testing::position(-300, 0);
+        let (_, recv) = scene.player(start, testing::velocity(10, 0), |p| {
+            p.set_vision(vision(100))
+        });
+        let still = testing::velocity(0, 0);
+        scene.player(testing::position(0, 0), still, target);
+        scene.run(testing::seconds(30));
+
+        let expected = vec![(testing::seconds(19), true)];
+        assert_eq!(sightings(&recv), expected);
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let mut scene = Scene::new();
+        let still = testing::velocity(0, 0);
+        let (_, recv) = scene.player(testing::position(0, 0), still, |p| {
+            p.set_vision(vision(100))
+        });
+        scene.player(testing::position(500, 0), still, target);
+        scene.run(testing::seconds(10));
+        assert_eq!(sightings(&recv), vec![]);
+    }
+
+    // the target changes speed at 10s, 200 away, before it comes into range
+    fn sightings_after_change(velocity: i32) -> Vec<(units::Time, bool)> {
+        let mut scene = Scene::new();
+        let still = testing::velocity(0, 0);
+        let (_, recv) = scene.player(testing::position(0, 0), still, |p| {
+            p.set_vision(vision(100))
+        });
+        let start = testing::position(300, 0);
+        let (id, _) = scene.player(start, testing::velocity(-10, 0), target);
+        let velocity = testing::velocity(velocity, 0);
+        let change = SetVelocity { id, velocity };
+        let when = testing::seconds(10);
+        sulphate::enqueue_absolute(&mut scene.time, change, when);
+        scene.run(testing::seconds(30));
+        sightings(&recv)
+    }
+
+    #[test]
+    fn test_look_replaced_by_sooner() {
+        let half = units::moments(8);
+        let expected = vec![(testing::seconds(14) + half, true)];
+        assert_eq!(sightings_after_change(-20), expected);
+    }
+
+    #[test]
+    fn test_look_replaced_by_never() {
+        assert_eq!(sightings_after_change(0), vec![]);
+    }
+}
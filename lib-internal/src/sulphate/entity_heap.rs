@@ -1,7 +1,9 @@
 use std::any;
 use std::any::Any;
 use std::collections;
+use std::hash;
 
+use lib_math;
 use rand;
 
 pub type ID = u64;
@@ -14,10 +16,36 @@ pub struct UID {
 }
 
 
+// the default hasher is randomly keyed, which would make any iteration
+// differ between runs, even with the same seed, and its algorithm may change
+// between releases, so we use FNV-1a, which is fixed and simple
+struct FixedHasher(u64);
+
+impl Default for FixedHasher {
+    fn default() -> FixedHasher {
+        FixedHasher(0xcbf29ce484222325)
+    }
+}
+
+impl hash::Hasher for FixedHasher {
+    fn finish(self: &Self) -> u64 {
+        self.0
+    }
+
+    fn write(self: &mut Self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+type FixedState = hash::BuildHasherDefault<FixedHasher>;
+
 // heap in the memory sense not the queue sense
 pub struct EntityHeap {
-    content: collections::HashMap<UID, Box<Any>>,
-    key_seed: rand::XorShiftRng,
+    content: collections::HashMap<UID, Box<Any>, FixedState>,
+    key_seed: lib_math::Random,
 }
 
 static DOWNCAST_ERROR: &'static str = "\
@@ -42,11 +70,23 @@ fn unwrap_box_mut<T: Any>(box_mut: &mut Box<Any>) -> &mut T {
 
 impl EntityHeap {
     pub fn new() -> EntityHeap {
-        let content = collections::HashMap::new();
-        let key_seed = rand::weak_rng();
+        EntityHeap::with_seed(rand::random())
+    }
+
+    /// the same seed gives the same IDs, given the same sequence of calls,
+    /// so that a scenario can be replayed exactly
+    pub fn with_seed(seed: u64) -> EntityHeap {
+        let content = collections::HashMap::default();
+        let key_seed = lib_math::Random::new(seed);
         EntityHeap { content, key_seed }
     }
 
+    /// random numbers for simulation code, drawn from the same sequence
+    /// as the IDs, so they are also determined by the seed
+    pub fn random(self: &mut Self) -> &mut lib_math::Random {
+        &mut self.key_seed
+    }
+
     pub fn get<T: Any>(self: &Self, id: ID) -> Option<&T> {
         let ty = any::TypeId::of::<T>();
         let uid = UID { id, ty };
@@ -64,7 +104,6 @@ impl EntityHeap {
     }
 
     fn new_id(self: &mut Self, ty: any::TypeId) -> ID {
-        use rand::Rng;
         loop {
             let id = self.key_seed.next_u64();
             let uid = UID { id, ty };
@@ -96,4 +135,43 @@ impl EntityHeap {
     }
 }
 
+#[cfg(test)]
+mod test_entity_heap {
+    use super::EntityHeap;
+
+    #[test]
+    fn test_seeded_ids() {
+        let mut first = EntityHeap::with_seed(42);
+        let mut second = EntityHeap::with_seed(42);
+
+        for n in 0..10 {
+            let a = first.add(n);
+            let b = second.add(n);
+            assert_eq!(a, b);
+            // ids are per type, so a second type draws from the same sequence
+            assert_eq!(first.add("entity"), second.add("entity"));
+        }
+        let a = first.add(10);
+        let b = second.add(10);
+        assert_eq!(a, b);
+        assert_eq!(first.remove::<i32>(a), Some(10));
+        assert_eq!(second.remove::<i32>(b), Some(10));
+        assert_eq!(first.add(11), second.add(11));
+    }
 
+    #[test]
+    fn test_seeded_random() {
+        let mut first = EntityHeap::with_seed(7);
+        let mut second = EntityHeap::with_seed(7);
+
+        first.add(());
+        second.add(());
+        for _ in 0..10 {
+            let a = first.random().next_u64();
+            let b = second.random().next_u64();
+            assert_eq!(a, b);
+        }
+        assert_eq!(first.random().up_to(100), second.random().up_to(100));
+        assert_eq!(first.add(()), second.add(()));
+    }
+}
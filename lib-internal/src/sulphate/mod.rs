@@ -18,6 +18,12 @@ pub struct World {
     matter: EntityHeap,
 }
 
+impl World {
+    pub fn new(space: space::CollisionSpace, matter: EntityHeap) -> World {
+        World { space, matter }
+    }
+}
+
 pub fn enqueue_absolute<E>(
     time: &mut EventQueue,
     event: E,
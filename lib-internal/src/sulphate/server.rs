@@ -44,14 +44,14 @@ fn duration_in_game(duration: time::Duration) -> units::Duration {
     let seconds = duration.as_secs();
     let nanos = duration.subsec_nanos();
     let time_s: units::Duration = (seconds as i32).into();
-    let time_n_num: units::Scalar = (nanos as i32).into();
+    let time_n_num: units::Duration = (nanos as i32).into();
     let time_n = time_n_num / 1_000_000_000;
     time_s + time_n
 }
 
 fn duration_real_time(duration: units::Duration) -> time::Duration {
-    let time_s: i32 = duration.into();
-    let time_frac = duration - time_s.into();
+    let time_s: i32 = duration.as_scalar().into();
+    let time_frac = duration.as_scalar() - time_s.into();
     let time_n: i32 = (time_frac * 1_000_000_000).into();
     time::Duration::new(time_s as u64, time_n as u32)
 }
@@ -186,7 +186,7 @@ fn create_server_local<F, R>(
 
     let r = f(&mut space, &mut time, &mut matter);
 
-    let world = sulphate::World { space, matter };
+    let world = sulphate::World::new(space, matter);
 
     let server = Server::new(time, world, upd, clock.clone());
 
@@ -0,0 +1,135 @@
+use lib_math::fixed;
+
+mod ops;
+
+// each quantity gets its own type, so that only dimensionally sound
+// combinations compile, e.g. Velocity * Duration = Displacement,
+// but Speed + Distance is an error.
+// the dimensionless lib_math types are still available for intermediate
+// calculations, via the from_*/as_* conversions
+
+// the number of fractional bits in every quantity, which sets both the
+// smallest distance and the length of an instant
+pub type Precision = fixed::N16;
+
+pub type Scalar = fixed::Scalar<Precision>;
+pub type Coord = fixed::Coord<Precision>;
+// the exact product of two Scalars, for sums of squares that would overflow
+pub type WideScalar = fixed::WideScalar<Precision>;
+
+// this is useful, for example, when you want the inner of a displacement and a
+// velocity, which has no unit of its own:
+// units::Vector::inner(velocity.as_vector(), displacement.as_vector())
+pub type Vector = fixed::Vector<Precision>;
+
+// shapes are dimensionless too, and are built from positions with as_position
+pub type Aabb = fixed::Aabb<Precision>;
+pub type Circle = fixed::Circle<Precision>;
+
+/// an instant in game time
+#[derive(Clone, Copy, Hash, Default, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Time(Coord);
+
+/// the difference between two instants
+#[derive(Clone, Copy, Hash, Default, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Duration(Scalar);
+
+#[derive(Clone, Copy, Hash, Default, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Distance(Scalar);
+
+#[derive(Clone, Copy, Hash, Default, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Speed(Scalar);
+
+#[derive(Clone, Copy, Hash, Default, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Mass(Scalar);
+
+// the vector quantities keep public components, which are dimensionless
+
+#[derive(Clone, Copy, Hash, Default, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Position {
+    pub x: Coord,
+    pub y: Coord,
+}
+
+#[derive(Clone, Copy, Hash, Default, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Displacement {
+    pub x: Scalar,
+    pub y: Scalar,
+}
+
+#[derive(Clone, Copy, Hash, Default, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Velocity {
+    pub x: Scalar,
+    pub y: Scalar,
+}
+
+#[derive(Clone, Copy, Hash, Default, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Acceleration {
+    pub x: Scalar,
+    pub y: Scalar,
+}
+
+impl Time {
+    pub fn from_coord(coord: Coord) -> Time {
+        Time(coord)
+    }
+
+    pub fn as_coord(self: Time) -> Coord {
+        self.0
+    }
+}
+
+impl Position {
+    pub fn from_position(
+        position: fixed::Position<Precision>,
+    ) -> Position {
+        let fixed::Position { x, y } = position;
+        Position { x, y }
+    }
+
+    pub fn as_position(self: Position) -> fixed::Position<Precision> {
+        let Position { x, y } = self;
+        fixed::Position { x, y }
+    }
+}
+
+impl Displacement {
+    pub fn magnitude(self: Displacement) -> Distance {
+        Distance(self.as_vector().magnitude())
+    }
+}
+
+impl Velocity {
+    pub fn magnitude(self: Velocity) -> Speed {
+        Speed(self.as_vector().magnitude())
+    }
+}
+
+// meant to be a minimal unit of time for rendering
+// things that ought to be seen, will last at least this long
+// so by keeping the framerate above 16, these things will be seen!
+
+pub const MOMENT_RATE: u16 = 16;
+
+pub fn moments(num: i32) -> Duration {
+    let as_scalar: Scalar = num.into();
+    Duration(as_scalar / MOMENT_RATE as i64)
+}
+
+pub fn instants(num: i64) -> Duration {
+    Duration(Scalar::from_bits(num))
+}
@@ -0,0 +1,467 @@
+use std::fmt;
+use std::ops;
+
+use units::{Scalar, Vector};
+use units::{Time, Duration, Distance, Speed, Mass};
+use units::{Position, Displacement, Velocity, Acceleration};
+
+// a one dimensional quantity that can be summed and scaled
+macro_rules! scalar_unit {
+    ($unit:ident) => {
+        impl $unit {
+            pub fn from_scalar(scalar: Scalar) -> $unit {
+                $unit(scalar)
+            }
+
+            pub fn as_scalar(self: $unit) -> Scalar {
+                self.0
+            }
+        }
+
+        impl From<i32> for $unit {
+            fn from(val: i32) -> $unit {
+                $unit(val.into())
+            }
+        }
+
+        impl ops::Neg for $unit {
+            type Output = $unit;
+            fn neg(self: $unit) -> $unit {
+                $unit(-self.0)
+            }
+        }
+
+        impl ops::Add for $unit {
+            type Output = $unit;
+            fn add(self: $unit, other: $unit) -> $unit {
+                $unit(self.0 + other.0)
+            }
+        }
+
+        impl ops::Sub for $unit {
+            type Output = $unit;
+            fn sub(self: $unit, other: $unit) -> $unit {
+                $unit(self.0 - other.0)
+            }
+        }
+
+        impl ops::AddAssign for $unit {
+            fn add_assign(self: &mut $unit, other: $unit) {
+                self.0 += other.0;
+            }
+        }
+
+        impl ops::SubAssign for $unit {
+            fn sub_assign(self: &mut $unit, other: $unit) {
+                self.0 -= other.0;
+            }
+        }
+
+        impl ops::Mul<Scalar> for $unit {
+            type Output = $unit;
+            fn mul(self: $unit, other: Scalar) -> $unit {
+                $unit(self.0 * other)
+            }
+        }
+
+        impl ops::Mul<$unit> for Scalar {
+            type Output = $unit;
+            fn mul(self: Scalar, other: $unit) -> $unit {
+                $unit(self * other.0)
+            }
+        }
+
+        impl ops::Mul<i64> for $unit {
+            type Output = $unit;
+            fn mul(self: $unit, other: i64) -> $unit {
+                $unit(self.0 * other)
+            }
+        }
+
+        impl ops::Div<Scalar> for $unit {
+            type Output = $unit;
+            fn div(self: $unit, other: Scalar) -> $unit {
+                $unit(self.0 / other)
+            }
+        }
+
+        impl ops::Div<i64> for $unit {
+            type Output = $unit;
+            fn div(self: $unit, other: i64) -> $unit {
+                $unit(self.0 / other)
+            }
+        }
+
+        // the ratio of two quantities of the same unit has no unit
+        impl ops::Div for $unit {
+            type Output = Scalar;
+            fn div(self: $unit, other: $unit) -> Scalar {
+                self.0 / other.0
+            }
+        }
+
+        impl fmt::Display for $unit {
+            fn fmt(
+                self: &Self,
+                f: &mut fmt::Formatter,
+            ) -> Result<(), fmt::Error> {
+                write!(f, "{}", self.0)
+            }
+        }
+    }
+}
+
+// a two dimensional quantity that can be summed and scaled
+macro_rules! vector_unit {
+    ($unit:ident) => {
+        impl $unit {
+            pub fn from_vector(vector: Vector) -> $unit {
+                let Vector { x, y } = vector;
+                $unit { x, y }
+            }
+
+            pub fn as_vector(self: $unit) -> Vector {
+                let $unit { x, y } = self;
+                Vector { x, y }
+            }
+        }
+
+        impl ops::Neg for $unit {
+            type Output = $unit;
+            fn neg(self: $unit) -> $unit {
+                $unit::from_vector(-self.as_vector())
+            }
+        }
+
+        impl ops::Add for $unit {
+            type Output = $unit;
+            fn add(self: $unit, other: $unit) -> $unit {
+                $unit::from_vector(self.as_vector() + other.as_vector())
+            }
+        }
+
+        impl ops::Sub for $unit {
+            type Output = $unit;
+            fn sub(self: $unit, other: $unit) -> $unit {
+                $unit::from_vector(self.as_vector() - other.as_vector())
+            }
+        }
+
+        impl ops::AddAssign for $unit {
+            fn add_assign(self: &mut $unit, other: $unit) {
+                *self = *self + other;
+            }
+        }
+
+        impl ops::SubAssign for $unit {
+            fn sub_assign(self: &mut $unit, other: $unit) {
+                *self = *self - other;
+            }
+        }
+
+        impl ops::Mul<Scalar> for $unit {
+            type Output = $unit;
+            fn mul(self: $unit, other: Scalar) -> $unit {
+                $unit::from_vector(self.as_vector() * other)
+            }
+        }
+
+        impl ops::Mul<$unit> for Scalar {
+            type Output = $unit;
+            fn mul(self: Scalar, other: $unit) -> $unit {
+                other * self
+            }
+        }
+
+        impl ops::Mul<i64> for $unit {
+            type Output = $unit;
+            fn mul(self: $unit, other: i64) -> $unit {
+                $unit::from_vector(self.as_vector() * other)
+            }
+        }
+
+        impl ops::Div<Scalar> for $unit {
+            type Output = $unit;
+            fn div(self: $unit, other: Scalar) -> $unit {
+                $unit::from_vector(self.as_vector() / other)
+            }
+        }
+
+        impl ops::Div<i64> for $unit {
+            type Output = $unit;
+            fn div(self: $unit, other: i64) -> $unit {
+                $unit::from_vector(self.as_vector() / other)
+            }
+        }
+
+        impl ops::MulAssign<Scalar> for $unit {
+            fn mul_assign(self: &mut $unit, other: Scalar) {
+                *self = *self * other;
+            }
+        }
+
+        impl ops::MulAssign<i64> for $unit {
+            fn mul_assign(self: &mut $unit, other: i64) {
+                *self = *self * other;
+            }
+        }
+
+        impl ops::DivAssign<Scalar> for $unit {
+            fn div_assign(self: &mut $unit, other: Scalar) {
+                *self = *self / other;
+            }
+        }
+
+        impl ops::DivAssign<i64> for $unit {
+            fn div_assign(self: &mut $unit, other: i64) {
+                *self = *self / other;
+            }
+        }
+
+        impl fmt::Display for $unit {
+            fn fmt(
+                self: &Self,
+                f: &mut fmt::Formatter,
+            ) -> Result<(), fmt::Error> {
+                write!(f, "{}", self.as_vector())
+            }
+        }
+    }
+}
+
+// rate * duration = amount, and the two divisions that undo it
+macro_rules! scalar_product {
+    ($rate:ident * $dur:ident = $amount:ident) => {
+        impl ops::Mul<$dur> for $rate {
+            type Output = $amount;
+            fn mul(self: $rate, other: $dur) -> $amount {
+                $amount(self.0 * other.0)
+            }
+        }
+
+        impl ops::Mul<$rate> for $dur {
+            type Output = $amount;
+            fn mul(self: $dur, other: $rate) -> $amount {
+                $amount(self.0 * other.0)
+            }
+        }
+
+        impl ops::Div<$dur> for $amount {
+            type Output = $rate;
+            fn div(self: $amount, other: $dur) -> $rate {
+                $rate(self.0 / other.0)
+            }
+        }
+
+        impl ops::Div<$rate> for $amount {
+            type Output = $dur;
+            fn div(self: $amount, other: $rate) -> $dur {
+                $dur(self.0 / other.0)
+            }
+        }
+    }
+}
+
+// as above, but with a vector rate and amount,
+// since a vector can't be divided by a vector
+macro_rules! vector_product {
+    ($rate:ident * $dur:ident = $amount:ident) => {
+        impl ops::Mul<$dur> for $rate {
+            type Output = $amount;
+            fn mul(self: $rate, other: $dur) -> $amount {
+                $amount::from_vector(self.as_vector() * other.0)
+            }
+        }
+
+        impl ops::Mul<$rate> for $dur {
+            type Output = $amount;
+            fn mul(self: $dur, other: $rate) -> $amount {
+                other * self
+            }
+        }
+
+        impl ops::Div<$dur> for $amount {
+            type Output = $rate;
+            fn div(self: $amount, other: $dur) -> $rate {
+                $rate::from_vector(self.as_vector() / other.0)
+            }
+        }
+    }
+}
+
+scalar_unit!(Duration);
+scalar_unit!(Distance);
+scalar_unit!(Speed);
+scalar_unit!(Mass);
+
+vector_unit!(Displacement);
+vector_unit!(Velocity);
+vector_unit!(Acceleration);
+
+scalar_product!(Speed * Duration = Distance);
+vector_product!(Velocity * Duration = Displacement);
+vector_product!(Acceleration * Duration = Velocity);
+
+// Time and Position are points rather than quantities,
+// so they can only be offset, or subtracted to find the offset
+
+impl ops::Add<Duration> for Time {
+    type Output = Time;
+    fn add(self: Time, other: Duration) -> Time {
+        Time(self.0 + other.0)
+    }
+}
+
+impl ops::Add<Time> for Duration {
+    type Output = Time;
+    fn add(self: Duration, other: Time) -> Time {
+        other + self
+    }
+}
+
+impl ops::Sub<Duration> for Time {
+    type Output = Time;
+    fn sub(self: Time, other: Duration) -> Time {
+        Time(self.0 - other.0)
+    }
+}
+
+impl ops::Sub for Time {
+    type Output = Duration;
+    fn sub(self: Time, other: Time) -> Duration {
+        Duration(self.0 - other.0)
+    }
+}
+
+impl ops::AddAssign<Duration> for Time {
+    fn add_assign(self: &mut Time, other: Duration) {
+        self.0 += other.0;
+    }
+}
+
+impl ops::SubAssign<Duration> for Time {
+    fn sub_assign(self: &mut Time, other: Duration) {
+        self.0 -= other.0;
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(self: &Self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl ops::Add<Displacement> for Position {
+    type Output = Position;
+    fn add(self: Position, other: Displacement) -> Position {
+        Position::from_position(self.as_position() + other.as_vector())
+    }
+}
+
+impl ops::Add<Position> for Displacement {
+    type Output = Position;
+    fn add(self: Displacement, other: Position) -> Position {
+        other + self
+    }
+}
+
+impl ops::Sub<Displacement> for Position {
+    type Output = Position;
+    fn sub(self: Position, other: Displacement) -> Position {
+        Position::from_position(self.as_position() - other.as_vector())
+    }
+}
+
+impl ops::Sub for Position {
+    type Output = Displacement;
+    fn sub(self: Position, other: Position) -> Displacement {
+        Displacement::from_vector(self.as_position() - other.as_position())
+    }
+}
+
+impl ops::AddAssign<Displacement> for Position {
+    fn add_assign(self: &mut Position, other: Displacement) {
+        *self = *self + other;
+    }
+}
+
+impl ops::SubAssign<Displacement> for Position {
+    fn sub_assign(self: &mut Position, other: Displacement) {
+        *self = *self - other;
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(self: &Self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.as_position())
+    }
+}
+
+#[cfg(test)]
+mod test_units {
+    use units::{self, Scalar, Vector};
+    use units::{Duration, Distance, Speed, Mass};
+    use units::{Displacement, Velocity, Acceleration};
+
+    fn vector(x: i32, y: i32) -> Vector {
+        Vector { x: x.into(), y: y.into() }
+    }
+
+    #[test]
+    fn test_scalar_product() {
+        let speed: Speed = 6.into();
+        let duration: Duration = 3.into();
+        let distance: Distance = 18.into();
+
+        assert_eq!(speed * duration, distance);
+        assert_eq!(duration * speed, distance);
+        assert_eq!(distance / duration, speed);
+        assert_eq!(distance / speed, duration);
+
+        // half a second at 6 per second
+        assert_eq!(speed * units::moments(8), Distance::from(3));
+    }
+
+    #[test]
+    fn test_vector_product() {
+        let velocity = Velocity::from_vector(vector(4, -2));
+        let duration: Duration = 3.into();
+        let displacement = Displacement::from_vector(vector(12, -6));
+
+        assert_eq!(velocity * duration, displacement);
+        assert_eq!(duration * velocity, displacement);
+        assert_eq!(displacement / duration, velocity);
+
+        let acceleration = Acceleration::from_vector(vector(-1, 5));
+        let change = Velocity::from_vector(vector(-3, 15));
+
+        assert_eq!(acceleration * duration, change);
+        assert_eq!(duration * acceleration, change);
+        assert_eq!(change / duration, acceleration);
+    }
+
+    #[test]
+    fn test_scalar_round_trip() {
+        let scalar = Scalar::from_bits(-123457);
+
+        assert_eq!(Duration::from_scalar(scalar).as_scalar(), scalar);
+        assert_eq!(Distance::from_scalar(scalar).as_scalar(), scalar);
+        assert_eq!(Speed::from_scalar(scalar).as_scalar(), scalar);
+        assert_eq!(Mass::from_scalar(scalar).as_scalar(), scalar);
+
+        assert_eq!(Distance::from_scalar(7.into()), Distance::from(7));
+        assert_eq!(units::instants(1).as_scalar(), Scalar::from_bits(1));
+    }
+
+    #[test]
+    fn test_vector_round_trip() {
+        let vector = Vector {
+            x: Scalar::from_bits(98765),
+            y: Scalar::from_bits(-4321),
+        };
+
+        assert_eq!(Displacement::from_vector(vector).as_vector(), vector);
+        assert_eq!(Velocity::from_vector(vector).as_vector(), vector);
+        assert_eq!(Acceleration::from_vector(vector).as_vector(), vector);
+    }
+}
@@ -0,0 +1,141 @@
+use std::cmp;
+
+use fixed::{Frac, Aabb, Position, Vector, Scalar};
+
+impl<F: Frac> Aabb<F> {
+    /// the smallest box with both points as corners, in either order
+    pub fn new(one: Position<F>, other: Position<F>) -> Aabb<F> {
+        let min = Position {
+            x: cmp::min(one.x, other.x),
+            y: cmp::min(one.y, other.y),
+        };
+        let max = Position {
+            x: cmp::max(one.x, other.x),
+            y: cmp::max(one.y, other.y),
+        };
+        Aabb { min, max }
+    }
+
+    pub fn from_point(point: Position<F>) -> Aabb<F> {
+        Aabb { min: point, max: point }
+    }
+
+    pub fn size(self: Aabb<F>) -> Vector<F> {
+        self.max - self.min
+    }
+
+    pub fn centre(self: Aabb<F>) -> Position<F> {
+        self.min + self.size() / 2
+    }
+
+    /// the smallest box containing both boxes
+    pub fn union(self: Aabb<F>, other: Aabb<F>) -> Aabb<F> {
+        let min = Position {
+            x: cmp::min(self.min.x, other.min.x),
+            y: cmp::min(self.min.y, other.min.y),
+        };
+        let max = Position {
+            x: cmp::max(self.max.x, other.max.x),
+            y: cmp::max(self.max.y, other.max.y),
+        };
+        Aabb { min, max }
+    }
+
+    pub fn contains(self: Aabb<F>, point: Position<F>) -> bool {
+        self.min.x <= point.x && point.x <= self.max.x
+            && self.min.y <= point.y && point.y <= self.max.y
+    }
+
+    /// the point in the box nearest to point, which is point itself if it
+    /// is inside
+    pub fn nearest(self: Aabb<F>, point: Position<F>) -> Position<F> {
+        let x = cmp::min(cmp::max(point.x, self.min.x), self.max.x);
+        let y = cmp::min(cmp::max(point.y, self.min.y), self.max.y);
+        Position { x, y }
+    }
+
+    /// boxes that only share an edge or corner still intersect
+    pub fn intersects(self: Aabb<F>, other: Aabb<F>) -> bool {
+        self.min.x <= other.max.x && other.min.x <= self.max.x
+            && self.min.y <= other.max.y && other.min.y <= self.max.y
+    }
+
+    /// moves every edge outwards by margin, which must not be negative
+    pub fn expand(self: Aabb<F>, margin: Scalar<F>) -> Aabb<F> {
+        debug_assert!(margin >= 0, "Aabb expanded by negative margin");
+        let offset = Vector { x: margin, y: margin };
+        let min = self.min - offset;
+        let max = self.max + offset;
+        Aabb { min, max }
+    }
+}
+
+#[cfg(test)]
+mod test_aabb {
+    use {Aabb, Position, Vector};
+
+    fn pos(x: i32, y: i32) -> Position {
+        Position::default() + Vector { x: x.into(), y: y.into() }
+    }
+
+    #[test]
+    fn test_new() {
+        let aabb = Aabb::new(pos(4, -1), pos(-2, 3));
+        assert_eq!(aabb, Aabb { min: pos(-2, -1), max: pos(4, 3) });
+        assert_eq!(aabb.size(), pos(6, 4) - pos(0, 0));
+        assert_eq!(aabb.centre(), pos(1, 1));
+        assert_eq!(Aabb::from_point(pos(3, 3)).size(), Vector::default());
+    }
+
+    #[test]
+    fn test_union() {
+        let one = Aabb::new(pos(0, 0), pos(2, 2));
+        let other = Aabb::new(pos(5, -3), pos(6, 1));
+        let both = Aabb::new(pos(0, -3), pos(6, 2));
+        assert_eq!(one.union(other), both);
+        assert_eq!(other.union(one), both);
+        assert_eq!(one.union(Aabb::from_point(pos(1, 1))), one);
+    }
+
+    #[test]
+    fn test_contains() {
+        let aabb = Aabb::new(pos(0, 0), pos(4, 2));
+        assert!(aabb.contains(pos(1, 1)));
+        assert!(aabb.contains(pos(4, 0)));
+        assert!(!aabb.contains(pos(5, 1)));
+        assert!(!aabb.contains(pos(2, -1)));
+    }
+
+    #[test]
+    fn test_nearest() {
+        let aabb = Aabb::new(pos(0, 0), pos(4, 2));
+        assert_eq!(aabb.nearest(pos(1, 1)), pos(1, 1));
+        assert_eq!(aabb.nearest(pos(-3, 1)), pos(0, 1));
+        assert_eq!(aabb.nearest(pos(7, 9)), pos(4, 2));
+        assert_eq!(aabb.nearest(pos(2, -5)), pos(2, 0));
+    }
+
+    #[test]
+    fn test_intersects() {
+        let aabb = Aabb::new(pos(0, 0), pos(4, 4));
+        let overlap = Aabb::new(pos(3, 3), pos(6, 6));
+        let touching = Aabb::new(pos(4, 1), pos(5, 2));
+        let inside = Aabb::new(pos(1, 1), pos(2, 2));
+        let apart = Aabb::new(pos(5, 0), pos(6, 4));
+        let diagonal = Aabb::new(pos(5, 5), pos(6, 6));
+        assert!(aabb.intersects(overlap));
+        assert!(aabb.intersects(touching));
+        assert!(aabb.intersects(inside));
+        assert!(inside.intersects(aabb));
+        assert!(!aabb.intersects(apart));
+        assert!(!aabb.intersects(diagonal));
+    }
+
+    #[test]
+    fn test_expand() {
+        let aabb = Aabb::new(pos(0, 0), pos(4, 2));
+        let expanded = Aabb::new(pos(-1, -1), pos(5, 3));
+        assert_eq!(aabb.expand(1.into()), expanded);
+        assert_eq!(aabb.expand(0.into()), aabb);
+    }
+}
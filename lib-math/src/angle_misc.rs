@@ -0,0 +1,270 @@
+use std::cmp;
+use std::fmt;
+
+use {Angle, Scalar};
+use fixed;
+use fixed::Frac;
+
+// everything here is done with integer CORDIC iterations, so that results
+// are bit-identical on every machine, unlike the float trig functions
+
+// atan(2 ^ -i) as a fraction of a whole turn, times 2 ^ 32
+const ATAN_TABLE: [i64; 31] = [
+    536870912, 316933406, 167458907, 85004756, 42667331, 21354465, 10679838,
+    5340245, 2670163, 1335087, 667544, 333772, 166886, 83443, 41722, 20861,
+    10430, 5215, 2608, 1304, 652, 326, 163, 81, 41, 20, 10, 5, 3, 1, 1,
+];
+
+// the product of 1 / sqrt(1 + 2 ^ -2i), which undoes the scaling that
+// the CORDIC rotations introduce, times 2 ^ 30
+const CORDIC_GAIN: i64 = 652032874;
+
+// number of fractional bits used in the CORDIC iterations
+pub(crate) const PRECISION: u32 = 30;
+
+// 2 ^ 48 / 2pi and 2pi * 2 ^ 48
+const TURNS_PER_RADIAN: i128 = 44798133900177;
+const RADIANS_PER_TURN: i128 = 1768559438007110;
+
+const QUARTER_TURN: u32 = 1 << 30;
+const HALF_TURN: u32 = 1 << 31;
+
+// angles only convert to and from the default precision,
+// Vector works with the CORDIC results directly
+impl Angle {
+    /// creates an angle from a fraction of a turn, wrapping around
+    pub fn from_turns(turns: Scalar) -> Angle {
+        Angle((turns.into_bits() << 16) as u32)
+    }
+
+    pub fn from_degrees(degrees: Scalar) -> Angle {
+        let bits = degrees.into_bits() as i128;
+        Angle(round_div(bits << 16, 360) as u32)
+    }
+
+    pub fn from_radians(radians: Scalar) -> Angle {
+        let bits = radians.into_bits() as i128;
+        Angle(round_shift(bits * TURNS_PER_RADIAN, 32) as u32)
+    }
+
+    /// the fraction of a turn, between -1/2 inclusive and 1/2 exclusive
+    pub fn turns(self: Angle) -> Scalar {
+        let signed = self.0 as i32 as i64;
+        Scalar::from_bits(round_shift(signed as i128, 16) as i64)
+    }
+
+    /// between -180 inclusive and 180 exclusive
+    pub fn degrees(self: Angle) -> Scalar {
+        let signed = self.0 as i32 as i128;
+        Scalar::from_bits(round_shift(signed * 360, 16) as i64)
+    }
+
+    /// between -pi inclusive and pi exclusive
+    pub fn radians(self: Angle) -> Scalar {
+        let signed = self.0 as i32 as i128;
+        Scalar::from_bits(round_shift(signed * RADIANS_PER_TURN, 64) as i64)
+    }
+
+    pub fn quarter_turn() -> Angle {
+        Angle(QUARTER_TURN)
+    }
+
+    pub fn half_turn() -> Angle {
+        Angle(HALF_TURN)
+    }
+
+    pub fn sin(self: Angle) -> Scalar {
+        let (_, sin) = sin_cos_bits(self);
+        from_cordic(sin)
+    }
+
+    pub fn cos(self: Angle) -> Scalar {
+        let (cos, _) = sin_cos_bits(self);
+        from_cordic(cos)
+    }
+
+    /// the angle from the positive x axis to the point (x, y),
+    /// zero if both are zero
+    pub fn atan2(y: Scalar, x: Scalar) -> Angle {
+        atan2_bits(y.into_bits(), x.into_bits())
+    }
+}
+
+// divides, rounding to nearest and ties away from zero
+fn round_div(val: i128, div: i128) -> i128 {
+    debug_assert!(div > 0);
+    if val < 0 {
+        -((-val + div / 2) / div)
+    } else {
+        (val + div / 2) / div
+    }
+}
+
+pub(crate) fn round_shift(val: i128, shift: u32) -> i128 {
+    round_div(val, 1 << shift)
+}
+
+// rounds a CORDIC result to the precision of a Scalar
+pub(crate) fn from_cordic<F: Frac>(bits: i64) -> fixed::Scalar<F> {
+    let shift = PRECISION - F::BITS;
+    fixed::Scalar::from_bits(round_shift(bits as i128, shift) as i64)
+}
+
+// (cos, sin) with PRECISION fractional bits
+pub(crate) fn sin_cos_bits(angle: Angle) -> (i64, i64) {
+    // CORDIC only converges within about a quarter turn of zero,
+    // so rotate the other half of the circle by a half turn first
+    let mut angle = angle.0;
+    let flip = QUARTER_TURN < angle && angle <= 3 * QUARTER_TURN;
+    if flip {
+        angle = angle.wrapping_sub(HALF_TURN);
+    }
+
+    let mut z = angle as i32 as i64;
+    let (mut x, mut y) = match angle {
+        0 => return with_flip(flip, 1 << PRECISION, 0),
+        QUARTER_TURN => return with_flip(flip, 0, 1 << PRECISION),
+        _ => (CORDIC_GAIN, 0),
+    };
+    // every rotation has to happen even once there's no angle left, since
+    // CORDIC_GAIN only undoes the scaling of all of them together
+    for (i, &step) in ATAN_TABLE.iter().enumerate() {
+        let (dx, dy) = (y >> i, x >> i);
+        if z > 0 {
+            x -= dx;
+            y += dy;
+            z -= step;
+        } else {
+            x += dx;
+            y -= dy;
+            z += step;
+        }
+    }
+
+    with_flip(flip, x, y)
+}
+
+fn with_flip(flip: bool, x: i64, y: i64) -> (i64, i64) {
+    if flip {
+        (-x, -y)
+    } else {
+        (x, y)
+    }
+}
+
+pub(crate) fn atan2_bits(y: i64, x: i64) -> Angle {
+    // the axes are common, and CORDIC would only approximate them
+    if y == 0 {
+        return if x < 0 { Angle::half_turn() } else { Angle::default() };
+    }
+    if x == 0 {
+        let quarter = Angle::quarter_turn();
+        return if y < 0 { -quarter } else { quarter };
+    }
+
+    // move the vector into the right half plane, where CORDIC converges
+    let (mut x, mut y, mut z) = if x < 0 {
+        (-(x as i128), -(y as i128), HALF_TURN as i64)
+    } else {
+        (x as i128, y as i128, 0)
+    };
+
+    // scale so that the largest component has about PRECISION + 2 bits,
+    // enough precision for the small angles, and no overflow for the large
+    let largest = cmp::max(x.abs(), y.abs());
+    let bits = 128 - largest.leading_zeros();
+    let target = PRECISION + 2;
+    if bits > target {
+        x >>= bits - target;
+        y >>= bits - target;
+    } else {
+        x <<= target - bits;
+        y <<= target - bits;
+    }
+
+    for (i, &step) in ATAN_TABLE.iter().enumerate() {
+        if y == 0 {
+            break;
+        }
+        let (dx, dy) = (y >> i, x >> i);
+        if y > 0 {
+            x += dx;
+            y -= dy;
+            z += step;
+        } else {
+            x -= dx;
+            y += dy;
+            z -= step;
+        }
+    }
+
+    Angle(z as u32)
+}
+
+impl fmt::Debug for Angle {
+    fn fmt(self: &Self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{} turns", self.turns())
+    }
+}
+
+#[cfg(test)]
+mod test_trig {
+    use {Angle, Scalar, Vector};
+
+    // the CORDIC results should be within a couple of bits of the true values
+    fn assert_close(actual: Scalar, expected: f64, what: &str) {
+        let expected_bits = (expected * (1 << 16) as f64).round() as i64;
+        let err = (actual.into_bits() - expected_bits).abs();
+        assert!(err <= 1, "{} was {}, expected {}", what, actual, expected);
+    }
+
+    #[test]
+    fn test_sin_cos_sweep() {
+        for i in 0..4096u32 {
+            let angle = Angle(i.wrapping_mul(1048573));
+            let turns = angle.0 as f64 / 4294967296.0;
+            let radians = turns * 2.0 * ::std::f64::consts::PI;
+            assert_close(angle.sin(), radians.sin(), "sin");
+            assert_close(angle.cos(), radians.cos(), "cos");
+        }
+    }
+
+    #[test]
+    fn test_exact_values() {
+        let quarter = Angle::quarter_turn();
+        assert_eq!(quarter.sin(), 1);
+        assert_eq!(quarter.cos(), 0);
+        assert_eq!(Angle::half_turn().cos(), -1);
+        assert_eq!((-quarter).sin(), -1);
+        assert_eq!(Angle::default().cos(), 1);
+        assert_eq!(Angle::from_degrees(90.into()), quarter);
+        assert_eq!(Angle::from_degrees((-270).into()), quarter);
+        assert_eq!(Angle::from_turns(Scalar::from(1) / 4), quarter);
+        assert_eq!(quarter.degrees(), 90);
+        assert_eq!(Angle::half_turn().degrees(), -180);
+        assert_eq!(quarter + quarter, Angle::half_turn());
+    }
+
+    #[test]
+    fn test_radians() {
+        let pi = Scalar::from_bits(205887);
+        assert_eq!(Angle::half_turn().radians(), -pi);
+        let angle = Angle::from_radians(1.into());
+        assert_close(angle.radians(), 1.0, "radians");
+        assert_close(angle.sin(), 1.0f64.sin(), "sin");
+    }
+
+    #[test]
+    fn test_atan2() {
+        assert_eq!(Angle::atan2(0.into(), 0.into()), Angle::default());
+        assert_eq!(Angle::atan2(0.into(), 5.into()), Angle::default());
+        for i in 0..4096u32 {
+            let angle = Angle(i.wrapping_mul(1048573));
+            let radius = Vector { x: 1000.into(), y: 0.into() };
+            let back = radius.rotate(angle).angle();
+            // the rotated vector is rounded to 2 ^ -16 / 1000 of a radian
+            let err = (back - angle).0 as i32;
+            assert!(err.abs() < 1 << 6, "atan2 of {:?} gave {:?}", angle, back);
+        }
+    }
+}
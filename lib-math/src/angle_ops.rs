@@ -0,0 +1,40 @@
+use std::ops;
+
+use Angle;
+
+// angles wrap around, so all of these are wrapping operations
+
+impl ops::Neg for Angle {
+    type Output = Angle;
+    fn neg(self: Angle) -> Angle {
+        Angle(self.0.wrapping_neg())
+    }
+}
+
+impl ops::Add for Angle {
+    type Output = Angle;
+    fn add(mut self: Angle, other: Angle) -> Angle {
+        self += other;
+        self
+    }
+}
+
+impl ops::Sub for Angle {
+    type Output = Angle;
+    fn sub(mut self: Angle, other: Angle) -> Angle {
+        self -= other;
+        self
+    }
+}
+
+impl ops::AddAssign for Angle {
+    fn add_assign(self: &mut Angle, other: Angle) {
+        self.0 = self.0.wrapping_add(other.0);
+    }
+}
+
+impl ops::SubAssign for Angle {
+    fn sub_assign(self: &mut Angle, other: Angle) {
+        self.0 = self.0.wrapping_sub(other.0);
+    }
+}
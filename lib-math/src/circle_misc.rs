@@ -0,0 +1,93 @@
+use fixed::{Frac, Aabb, Circle, Position, Vector};
+
+impl<F: Frac> Circle<F> {
+    /// the smallest box containing the circle
+    pub fn bounds(self: Circle<F>) -> Aabb<F> {
+        let offset = Vector { x: self.radius, y: self.radius };
+        let min = self.centre - offset;
+        let max = self.centre + offset;
+        Aabb { min, max }
+    }
+
+    /// circles that only touch still intersect
+    pub fn intersects(self: Circle<F>, other: Circle<F>) -> bool {
+        let reach = self.radius + other.radius;
+        let centre_disp = other.centre - self.centre;
+        centre_disp.wide_squared() <= reach.wide_squared()
+    }
+
+    /// points on the boundary count as inside
+    pub fn contains(self: Circle<F>, point: Position<F>) -> bool {
+        let disp = point - self.centre;
+        disp.wide_squared() <= self.radius.wide_squared()
+    }
+
+    /// circles that only touch the box still intersect it
+    pub fn intersects_aabb(self: Circle<F>, bounds: Aabb<F>) -> bool {
+        self.contains(bounds.nearest(self.centre))
+    }
+
+    /// a box containing every point the circle passes over while its centre
+    /// moves in a straight line to end
+    pub fn swept_bounds(self: Circle<F>, end: Position<F>) -> Aabb<F> {
+        let moved = Circle { centre: end, radius: self.radius };
+        self.bounds().union(moved.bounds())
+    }
+}
+
+#[cfg(test)]
+mod test_circle {
+    use {Aabb, Circle, Position, Vector};
+
+    fn pos(x: i32, y: i32) -> Position {
+        Position::default() + Vector { x: x.into(), y: y.into() }
+    }
+
+    fn circle(x: i32, y: i32, radius: i32) -> Circle {
+        Circle { centre: pos(x, y), radius: radius.into() }
+    }
+
+    #[test]
+    fn test_bounds() {
+        let bounds = circle(1, 2, 3).bounds();
+        assert_eq!(bounds, Aabb::new(pos(-2, -1), pos(4, 5)));
+    }
+
+    #[test]
+    fn test_intersects() {
+        let one = circle(0, 0, 5);
+        assert!(one.intersects(circle(6, 8, 5)));
+        assert!(!one.intersects(circle(6, 8, 4)));
+        assert!(one.intersects(circle(1, 1, 1)));
+        // the bounds overlap, but the circles don't
+        assert!(!one.intersects(circle(7, 7, 4)));
+        assert!(one.bounds().intersects(circle(7, 7, 4).bounds()));
+    }
+
+    #[test]
+    fn test_intersects_aabb() {
+        let one = circle(0, 0, 5);
+        assert!(one.intersects_aabb(Aabb::new(pos(-1, -1), pos(1, 1))));
+        assert!(one.intersects_aabb(Aabb::new(pos(-9, -9), pos(9, 9))));
+        assert!(one.intersects_aabb(Aabb::new(pos(5, -1), pos(7, 1))));
+        assert!(one.intersects_aabb(Aabb::new(pos(3, 4), pos(6, 6))));
+        // the corner is outside, even though the bounds overlap
+        assert!(!one.intersects_aabb(Aabb::new(pos(4, 4), pos(6, 6))));
+    }
+
+    #[test]
+    fn test_contains() {
+        let one = circle(0, 0, 5);
+        assert!(one.contains(pos(3, 4)));
+        assert!(one.contains(pos(-5, 0)));
+        assert!(!one.contains(pos(4, 4)));
+    }
+
+    #[test]
+    fn test_swept_bounds() {
+        let swept = circle(0, 0, 1).swept_bounds(pos(10, -4));
+        assert_eq!(swept, Aabb::new(pos(-1, -5), pos(11, 1)));
+        let still = circle(3, 3, 2);
+        assert_eq!(still.swept_bounds(still.centre), still.bounds());
+    }
+}
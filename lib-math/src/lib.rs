@@ -1,32 +1,136 @@
 extern crate fix;
 extern crate typenum;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_test;
 
 // bunch of trait implementations
 mod scalar_assigns;
 mod scalar_ops;
 mod scalar_misc;
+mod scalar_checked;
+mod scalar_wide;
 mod vector_assigns;
 mod vector_ops;
 mod vector_misc;
+mod vector_checked;
+mod position_misc;
+mod scalar_parse;
+mod vector_parse;
+mod angle_ops;
+mod angle_misc;
+mod aabb_misc;
+mod circle_misc;
+mod random_misc;
+#[cfg(feature = "serde")]
+mod serde_impls;
+
+pub use scalar_parse::{Rounding, ParseError};
+
+pub use fixed::Frac;
 
 use fix::aliases::binary::IFix64;
-use typenum::N16;
 
-type Inner = IFix64<N16>;
+type Inner<F> = IFix64<F>;
 
-#[derive(Clone, Copy, Hash, Default, PartialEq, Eq, PartialOrd, Ord)]
-pub struct Scalar(Inner);
-#[derive(Clone, Copy, Hash, Default, PartialEq, Eq, PartialOrd, Ord)]
-pub struct Coord(Inner);
+/// the fixed point types, generic over their precision, which is given as
+/// the exponent of the smallest representable value, e.g. N16 for 2 ^ -16
+pub mod fixed {
+    use std::fmt;
+    use std::hash;
+    use std::marker;
 
-#[derive(Clone, Copy, Hash, Default, PartialEq, Eq, Debug)]
-pub struct Vector {
-    pub x: Scalar,
-    pub y: Scalar,
-}
-#[derive(Clone, Copy, Hash, Default, PartialEq, Eq, Debug)]
-pub struct Position {
-    pub x: Coord,
-    pub y: Coord,
+    use Inner;
+
+    pub use typenum::consts::{N8, N9, N10, N11, N12, N13, N14, N15, N16};
+    pub use typenum::consts::{N17, N18, N19, N20, N21, N22, N23, N24};
+
+    /// the precisions that the fixed point types support
+    pub trait Frac:
+        Copy + Default + hash::Hash + Ord + fmt::Debug + 'static
+    {
+        /// the number of fractional bits
+        const BITS: u32;
+    }
+
+    macro_rules! fracs {
+        ($($exp:ident = $bits:expr),*) => {
+            $(impl Frac for $exp { const BITS: u32 = $bits; })*
+        }
+    }
+
+    // parsing shifts up to 100 bits of decimal fraction by BITS,
+    // and CORDIC results only have 30 fractional bits, so 24 is the limit
+    fracs!(N8 = 8, N9 = 9, N10 = 10, N11 = 11, N12 = 12, N13 = 13,
+           N14 = 14, N15 = 15, N16 = 16, N17 = 17, N18 = 18, N19 = 19,
+           N20 = 20, N21 = 21, N22 = 22, N23 = 23, N24 = 24);
+
+    #[derive(Clone, Copy, Hash, Default, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct Scalar<F: Frac>(pub(crate) Inner<F>);
+    #[derive(Clone, Copy, Hash, Default, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct Coord<F: Frac>(pub(crate) Inner<F>);
+
+    /// an exact product of two Scalars, with twice as many fractional bits,
+    /// so that sums of products can be divided without overflowing
+    #[derive(Clone, Copy, Hash, Default, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct WideScalar<F: Frac>(
+        pub(crate) i128,
+        pub(crate) marker::PhantomData<F>,
+    );
+
+    #[derive(Clone, Copy, Hash, Default, PartialEq, Eq, Debug)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "serde", serde(bound = ""))]
+    pub struct Vector<F: Frac> {
+        pub x: Scalar<F>,
+        pub y: Scalar<F>,
+    }
+    #[derive(Clone, Copy, Hash, Default, PartialEq, Eq, Debug)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "serde", serde(bound = ""))]
+    pub struct Position<F: Frac> {
+        pub x: Coord<F>,
+        pub y: Coord<F>,
+    }
+
+    /// an axis aligned box, min is the corner with the smallest coordinates,
+    /// and the edges count as part of the box
+    #[derive(Clone, Copy, Hash, Default, PartialEq, Eq, Debug)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "serde", serde(bound = ""))]
+    pub struct Aabb<F: Frac> {
+        pub min: Position<F>,
+        pub max: Position<F>,
+    }
+    #[derive(Clone, Copy, Hash, Default, PartialEq, Eq, Debug)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "serde", serde(bound = ""))]
+    pub struct Circle<F: Frac> {
+        pub centre: Position<F>,
+        pub radius: Scalar<F>,
+    }
 }
 
+// the default precision, 48.16
+pub type Scalar = fixed::Scalar<fixed::N16>;
+pub type Coord = fixed::Coord<fixed::N16>;
+pub type WideScalar = fixed::WideScalar<fixed::N16>;
+pub type Vector = fixed::Vector<fixed::N16>;
+pub type Position = fixed::Position<fixed::N16>;
+pub type Aabb = fixed::Aabb<fixed::N16>;
+pub type Circle = fixed::Circle<fixed::N16>;
+
+/// a direction, stored as a fraction of a turn with 32 fractional bits,
+/// so that it wraps around naturally
+#[derive(Clone, Copy, Hash, Default, PartialEq, Eq)]
+pub struct Angle(u32);
+
+/// a deterministic random number generator, which gives the same sequence
+/// on every machine for a given seed, so that simulations can be replayed
+#[derive(Clone, Hash, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Random {
+    state: u64,
+}
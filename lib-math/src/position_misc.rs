@@ -0,0 +1,45 @@
+use fixed::{Frac, Position, Scalar};
+
+impl<F: Frac> Position<F> {
+    /// the point a fraction t of the way from self to other,
+    /// t is not limited to [0, 1]
+    pub fn lerp(
+        self: Position<F>,
+        other: Position<F>,
+        t: Scalar<F>,
+    ) -> Position<F> {
+        self + (other - self) * t
+    }
+
+    pub fn distance(self: Position<F>, other: Position<F>) -> Scalar<F> {
+        (other - self).magnitude()
+    }
+}
+
+#[cfg(test)]
+mod test_position {
+    use {Position, Vector, Scalar};
+
+    fn pos(x: i32, y: i32) -> Position {
+        Position::default() + Vector { x: x.into(), y: y.into() }
+    }
+
+    #[test]
+    fn test_lerp() {
+        let start = pos(-2, 6);
+        let end = pos(6, 2);
+        let quarter = Scalar::from(1) / 4;
+        assert_eq!(start.lerp(end, 0.into()), start);
+        assert_eq!(start.lerp(end, 1.into()), end);
+        assert_eq!(start.lerp(end, quarter), pos(0, 5));
+        assert_eq!(start.lerp(end, 2.into()), pos(14, -2));
+    }
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(pos(1, 1).distance(pos(4, 5)), 5);
+        assert_eq!(pos(4, 5).distance(pos(1, 1)), 5);
+        let far = pos(300_000, 0).distance(pos(0, 400_000));
+        assert_eq!(far, 500_000);
+    }
+}
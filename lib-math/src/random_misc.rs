@@ -0,0 +1,144 @@
+use {Angle, Inner, Random};
+use fixed::{Frac, Scalar, Coord, Vector, Position, Aabb};
+
+// this is splitmix64, which is fast, has no bad seeds,
+// and only needs integer arithmetic
+const GOLDEN_GAMMA: u64 = 0x9E3779B97F4A7C15;
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        Random { state: seed }
+    }
+
+    pub fn next_u64(self: &mut Random) -> u64 {
+        self.state = self.state.wrapping_add(GOLDEN_GAMMA);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// uniform between 0 and max inclusive
+    pub fn up_to(self: &mut Random, max: u64) -> u64 {
+        let count = match max.checked_add(1) {
+            Some(count) => count,
+            None => return self.next_u64(),
+        };
+        // taking the remainder of anything below 2 ^ 64 mod count would
+        // favour the small values, so try again instead
+        let threshold = count.wrapping_neg() % count;
+        loop {
+            let bits = self.next_u64();
+            if bits >= threshold {
+                return bits % count;
+            }
+        }
+    }
+
+    fn bits_between(self: &mut Random, min: i64, max: i64) -> i64 {
+        assert!(min <= max, "Random range is empty: {} to {}", min, max);
+        let range = (max as i128 - min as i128) as u64;
+        (min as i128 + self.up_to(range) as i128) as i64
+    }
+
+    /// uniform between min and max inclusive
+    pub fn scalar_between<F: Frac>(
+        self: &mut Random,
+        min: Scalar<F>,
+        max: Scalar<F>,
+    ) -> Scalar<F> {
+        let bits = self.bits_between(min.into_bits(), max.into_bits());
+        Scalar::from_bits(bits)
+    }
+
+    pub fn angle(self: &mut Random) -> Angle {
+        Angle((self.next_u64() >> 32) as u32)
+    }
+
+    /// a unit vector in a uniformly random direction
+    pub fn unit_vector<F: Frac>(self: &mut Random) -> Vector<F> {
+        let angle = self.angle();
+        Vector::from_angle(angle)
+    }
+
+    /// uniform over the box, including its edges
+    pub fn position_in<F: Frac>(
+        self: &mut Random,
+        aabb: Aabb<F>,
+    ) -> Position<F> {
+        let Aabb { min, max } = aabb;
+        let x = self.bits_between(min.x.0.bits, max.x.0.bits);
+        let y = self.bits_between(min.y.0.bits, max.y.0.bits);
+        let x = Coord(Inner::new(x));
+        let y = Coord(Inner::new(y));
+        Position { x, y }
+    }
+}
+
+#[cfg(test)]
+mod test_random {
+    use {Random, Scalar, Vector, Position, Aabb};
+
+    #[test]
+    fn test_deterministic() {
+        // the first output of splitmix64 seeded with zero
+        assert_eq!(Random::new(0).next_u64(), 0xE220A8397B1DCDAF);
+
+        let mut one = Random::new(12345);
+        let mut other = one.clone();
+        for _ in 0..100 {
+            assert_eq!(one.next_u64(), other.next_u64());
+        }
+        assert!(Random::new(1).next_u64() != Random::new(2).next_u64());
+    }
+
+    #[test]
+    fn test_up_to() {
+        let mut random = Random::new(7);
+        let mut seen = [false; 6];
+        for _ in 0..1000 {
+            let val = random.up_to(5);
+            seen[val as usize] = true;
+        }
+        assert!(seen.iter().all(|&seen| seen));
+        assert_eq!(random.up_to(0), 0);
+        random.up_to(u64::MAX);
+    }
+
+    #[test]
+    fn test_scalar_between() {
+        let mut random = Random::new(99);
+        let min = Scalar::from(-3) / 2;
+        let max = Scalar::from(2);
+        for _ in 0..1000 {
+            let val = random.scalar_between(min, max);
+            assert!(min <= val && val <= max, "{} out of range", val);
+        }
+        assert_eq!(random.scalar_between(max, max), max);
+        let full = Scalar::from_bits(i64::MIN);
+        random.scalar_between(full, Scalar::from_bits(i64::MAX));
+    }
+
+    #[test]
+    fn test_unit_vector() {
+        let mut random = Random::new(3);
+        for _ in 0..100 {
+            let unit: Vector = random.unit_vector();
+            let err = unit.magnitude() - Scalar::from(1);
+            assert!(err.into_bits().abs() <= 2, "{} is not a unit", unit);
+        }
+    }
+
+    #[test]
+    fn test_position_in() {
+        let mut random = Random::new(42);
+        let corner = Position::default() + Vector { x: 5.into(), y: 8.into() };
+        let aabb = Aabb::new(Position::default(), corner);
+        for _ in 0..1000 {
+            let pos = random.position_in(aabb);
+            assert!(aabb.contains(pos), "{} out of range", pos);
+        }
+        let point = Aabb::from_point(corner);
+        assert_eq!(random.position_in(point), corner);
+    }
+}
@@ -1,58 +1,82 @@
 use std::ops;
 
-use {Scalar, Coord};
+use fixed::{Frac, Scalar, Coord};
 
-impl ops::AddAssign for Scalar {
-    fn add_assign(self: &mut Scalar, other: Scalar) {
-        self.0 += other.0;
+impl<F: Frac> ops::AddAssign for Scalar<F> {
+    fn add_assign(self: &mut Scalar<F>, other: Scalar<F>) {
+        let result = self.0.bits.checked_add(other.0.bits);
+        debug_assert!(
+            result.is_some(),
+            "Scalar overflow: {:?} + {:?}", self, other
+        );
+        self.0.bits = self.0.bits.wrapping_add(other.0.bits);
     }
 }
 
-impl ops::AddAssign<Scalar> for Coord {
-    fn add_assign(self: &mut Coord, other: Scalar) {
-        self.0 += other.0;
+impl<F: Frac> ops::AddAssign<Scalar<F>> for Coord<F> {
+    fn add_assign(self: &mut Coord<F>, other: Scalar<F>) {
+        let result = self.0.bits.checked_add(other.0.bits);
+        debug_assert!(
+            result.is_some(),
+            "Coord overflow: {:?} + {:?}", self, other
+        );
+        self.0.bits = self.0.bits.wrapping_add(other.0.bits);
     }
 }
 
-impl ops::SubAssign for Scalar {
-    fn sub_assign(self: &mut Scalar, other: Scalar) {
-        self.0 -= other.0;
+impl<F: Frac> ops::SubAssign for Scalar<F> {
+    fn sub_assign(self: &mut Scalar<F>, other: Scalar<F>) {
+        let result = self.0.bits.checked_sub(other.0.bits);
+        debug_assert!(
+            result.is_some(),
+            "Scalar overflow: {:?} - {:?}", self, other
+        );
+        self.0.bits = self.0.bits.wrapping_sub(other.0.bits);
     }
 }
 
-impl ops::SubAssign<Scalar> for Coord {
-    fn sub_assign(self: &mut Coord, other: Scalar) {
-        self.0 -= other.0;
+impl<F: Frac> ops::SubAssign<Scalar<F>> for Coord<F> {
+    fn sub_assign(self: &mut Coord<F>, other: Scalar<F>) {
+        let result = self.0.bits.checked_sub(other.0.bits);
+        debug_assert!(
+            result.is_some(),
+            "Coord overflow: {:?} - {:?}", self, other
+        );
+        self.0.bits = self.0.bits.wrapping_sub(other.0.bits);
     }
 }
 
-impl ops::MulAssign for Scalar {
-    fn mul_assign(self: &mut Scalar, other: Scalar) {
+impl<F: Frac> ops::MulAssign for Scalar<F> {
+    fn mul_assign(self: &mut Scalar<F>, other: Scalar<F>) {
         *self = *self * other;
     }
 }
 
-impl ops::DivAssign for Scalar {
-    fn div_assign(self: &mut Scalar, other: Scalar) {
+impl<F: Frac> ops::DivAssign for Scalar<F> {
+    fn div_assign(self: &mut Scalar<F>, other: Scalar<F>) {
         *self = *self / other;
     }
 }
 
-impl ops::MulAssign<i64> for Scalar {
-    fn mul_assign(self: &mut Scalar, other: i64) {
-        self.0.bits *= other;
+impl<F: Frac> ops::MulAssign<i64> for Scalar<F> {
+    fn mul_assign(self: &mut Scalar<F>, other: i64) {
+        let result = self.0.bits.checked_mul(other);
+        debug_assert!(
+            result.is_some(),
+            "Scalar overflow: {:?} * {}", self, other
+        );
+        self.0.bits = self.0.bits.wrapping_mul(other);
     }
 }
 
-impl ops::DivAssign<i64> for Scalar {
-    fn div_assign(self: &mut Scalar, other: i64) {
+impl<F: Frac> ops::DivAssign<i64> for Scalar<F> {
+    fn div_assign(self: &mut Scalar<F>, other: i64) {
         self.0.bits /= other;
     }
 }
 
-impl ops::RemAssign for Scalar {
-    fn rem_assign(self: &mut Scalar, other: Scalar) {
+impl<F: Frac> ops::RemAssign for Scalar<F> {
+    fn rem_assign(self: &mut Scalar<F>, other: Scalar<F>) {
         self.0 %= other.0;
     }
 }
-
@@ -0,0 +1,197 @@
+use fixed::{Frac, Scalar, WideScalar};
+
+// the i128 intermediates are exact, these just check whether they fit back
+// into the i64 representation
+pub(crate) fn narrow(wide: i128) -> Option<i64> {
+    if i64::MIN as i128 <= wide && wide <= i64::MAX as i128 {
+        Some(wide as i64)
+    } else {
+        None
+    }
+}
+
+fn saturate(wide: i128) -> i64 {
+    if wide < 0 {
+        i64::MIN
+    } else {
+        i64::MAX
+    }
+}
+
+pub(crate) fn mul_bits<F: Frac>(x: i64, y: i64) -> i128 {
+    // same truncation as the fix crate, x * y / 2 ^ BITS
+    (x as i128 * y as i128) / (1 << F::BITS)
+}
+
+pub(crate) fn div_bits<F: Frac>(x: i64, y: i64) -> i128 {
+    // x * 2 ^ BITS / y, which always fits in i128
+    ((x as i128) << F::BITS) / y as i128
+}
+
+impl<F: Frac> Scalar<F> {
+    pub fn checked_neg(self: Scalar<F>) -> Option<Scalar<F>> {
+        self.0.bits.checked_neg().map(Scalar::from_bits)
+    }
+
+    pub fn checked_add(self: Scalar<F>, other: Scalar<F>) -> Option<Scalar<F>> {
+        self.0.bits.checked_add(other.0.bits).map(Scalar::from_bits)
+    }
+
+    pub fn checked_sub(self: Scalar<F>, other: Scalar<F>) -> Option<Scalar<F>> {
+        self.0.bits.checked_sub(other.0.bits).map(Scalar::from_bits)
+    }
+
+    pub fn checked_mul(self: Scalar<F>, other: Scalar<F>) -> Option<Scalar<F>> {
+        narrow(mul_bits::<F>(self.0.bits, other.0.bits)).map(Scalar::from_bits)
+    }
+
+    /// returns `None` on overflow or division by zero
+    pub fn checked_div(self: Scalar<F>, other: Scalar<F>) -> Option<Scalar<F>> {
+        if other.0.bits == 0 {
+            return None;
+        }
+        narrow(div_bits::<F>(self.0.bits, other.0.bits)).map(Scalar::from_bits)
+    }
+
+    pub fn saturating_add(self: Scalar<F>, other: Scalar<F>) -> Scalar<F> {
+        Scalar::from_bits(self.0.bits.saturating_add(other.0.bits))
+    }
+
+    pub fn saturating_sub(self: Scalar<F>, other: Scalar<F>) -> Scalar<F> {
+        Scalar::from_bits(self.0.bits.saturating_sub(other.0.bits))
+    }
+
+    pub fn saturating_mul(self: Scalar<F>, other: Scalar<F>) -> Scalar<F> {
+        let wide = mul_bits::<F>(self.0.bits, other.0.bits);
+        Scalar::from_bits(narrow(wide).unwrap_or_else(|| saturate(wide)))
+    }
+
+    /// panics on division by zero, like the integer equivalent
+    pub fn saturating_div(self: Scalar<F>, other: Scalar<F>) -> Scalar<F> {
+        assert!(other.0.bits != 0, "Scalar division by zero");
+        let wide = div_bits::<F>(self.0.bits, other.0.bits);
+        Scalar::from_bits(narrow(wide).unwrap_or_else(|| saturate(wide)))
+    }
+
+    /// computes `self * mul / div` without rounding or overflowing in between
+    pub fn mul_div(
+        self: Scalar<F>,
+        mul: Scalar<F>,
+        div: Scalar<F>,
+    ) -> Scalar<F> {
+        Scalar::wide_mul(self, mul) / WideScalar::from(div)
+    }
+
+    /// computes `self * num / den` with a single rounding, e.g. for scaling
+    /// by a ratio of inner products
+    pub fn mul_ratio(
+        self: Scalar<F>,
+        num: WideScalar<F>,
+        den: WideScalar<F>,
+    ) -> Scalar<F> {
+        let (num, den) = (num.into_bits(), den.into_bits());
+        assert!(den != 0, "Scalar division by zero");
+        // only enormous numerators lose precision, to keep the product in range
+        let num_bits = 128 - num.unsigned_abs().leading_zeros();
+        let shift = num_bits.saturating_sub(64);
+        let (num, den) = (num >> shift, den >> shift);
+        let result = if den == 0 {
+            None
+        } else {
+            narrow(self.0.bits as i128 * num / den)
+        };
+        debug_assert!(
+            result.is_some(),
+            "Scalar overflow: {:?} * {:?} / {:?}", self, num, den
+        );
+        let sign = self.0.bits.signum() as i128 * num.signum() * den.signum();
+        Scalar::from_bits(result.unwrap_or_else(|| saturate(sign)))
+    }
+
+    /// exact product, which can be summed and divided without overflow
+    pub fn wide_mul(self: Scalar<F>, other: Scalar<F>) -> WideScalar<F> {
+        WideScalar::from_bits(self.0.bits as i128 * other.0.bits as i128)
+    }
+
+    pub fn wide_squared(self: Scalar<F>) -> WideScalar<F> {
+        self.wide_mul(self)
+    }
+}
+
+#[cfg(test)]
+mod test_checked {
+    use {Scalar, WideScalar};
+
+    fn max() -> Scalar {
+        Scalar::from_bits(i64::MAX)
+    }
+
+    fn min() -> Scalar {
+        Scalar::from_bits(i64::MIN)
+    }
+
+    #[test]
+    fn test_checked_in_range() {
+        let x: Scalar = 300.into();
+        let y: Scalar = (-7).into();
+        assert_eq!(x.checked_add(y), Some(x + y));
+        assert_eq!(x.checked_sub(y), Some(x - y));
+        assert_eq!(x.checked_mul(y), Some(x * y));
+        assert_eq!(x.checked_div(y), Some(x / y));
+        assert_eq!(y.checked_neg(), Some(-y));
+    }
+
+    #[test]
+    fn test_checked_overflow() {
+        let big: Scalar = 1_000_000_000.into();
+        assert_eq!(max().checked_add(Scalar::from_bits(1)), None);
+        assert_eq!(min().checked_sub(Scalar::from_bits(1)), None);
+        assert_eq!(min().checked_neg(), None);
+        assert_eq!(big.checked_mul(big), None);
+        assert_eq!(max().checked_div(Scalar::from_bits(1 << 15)), None);
+        assert_eq!(big.checked_div(Scalar::default()), None);
+    }
+
+    #[test]
+    fn test_saturating() {
+        let big: Scalar = 1_000_000_000.into();
+        assert_eq!(big.saturating_mul(big), max());
+        assert_eq!(big.saturating_mul(-big), min());
+        assert_eq!(max().saturating_add(big), max());
+        assert_eq!(min().saturating_sub(big), min());
+        assert_eq!(max().saturating_div(Scalar::from_bits(-(1 << 15))), min());
+        let x: Scalar = 12.into();
+        assert_eq!(x.saturating_mul(x), 144);
+    }
+
+    #[test]
+    fn test_mul_matches_truncation() {
+        // both operands have a half, the product's low bits are truncated
+        let x = Scalar::from_bits(3);
+        let y = Scalar::from_bits(-(1 << 15));
+        assert_eq!((x * y).into_bits(), -1);
+        assert_eq!((x / Scalar::from(3)).into_bits(), 1);
+    }
+
+    #[test]
+    fn test_mul_div() {
+        // 2 ^ 30 squared would overflow in the intermediate
+        let big: Scalar = (1 << 30).into();
+        let small: Scalar = 4.into();
+        assert_eq!(big.mul_div(big, big), big);
+        assert_eq!(big.mul_div(small, big * small), 1);
+        let wide = big.wide_squared() - small.wide_squared();
+        assert_eq!(wide / WideScalar::from(big), big - Scalar::from_bits(1));
+        let den = (big * 3).wide_mul(big);
+        let third = small.mul_ratio(big.wide_squared(), den);
+        assert_eq!(third, Scalar::from(4) / 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Scalar overflow")]
+    #[cfg(debug_assertions)]
+    fn test_overflow_panics_with_operands() {
+        let big: Scalar = 1_000_000_000.into();
+        let _ = big * big;
+    }
+}
@@ -1,19 +1,50 @@
 use std::fmt;
 use std::cmp;
 
-use super::{Scalar, Coord, Inner};
+use Inner;
+use fixed::{Frac, Scalar, Coord, WideScalar, N16};
+use angle_misc::round_shift;
 
 
-impl Scalar {
-    pub fn from_bits(bits: i64) -> Scalar {
+impl<F: Frac> Scalar<F> {
+    pub fn from_bits(bits: i64) -> Scalar<F> {
         Scalar(Inner::new(bits))
     }
 
-    pub fn into_bits(self: Scalar) -> i64 {
+    pub fn into_bits(self: Scalar<F>) -> i64 {
         self.0.bits
     }
 
-    pub fn rough_sqrt(self: Scalar) -> Scalar {
+    /// square root rounded to the nearest representable value,
+    /// exact over the whole range of Scalar
+    pub fn sqrt(self: Scalar<F>) -> Scalar<F> {
+        let bits = self.0.bits;
+        debug_assert!(bits >= 0, "Square root of negative number");
+        WideScalar::from(cmp::max(self, Scalar::default())).sqrt()
+    }
+
+    pub fn squared(self: Scalar<F>) -> Scalar<F> {
+        self * self
+    }
+
+    /// the nearest value with another precision, ties away from zero
+    pub fn convert<G: Frac>(self: Scalar<F>) -> Scalar<G> {
+        Scalar::from_bits(convert_bits::<F, G>(self.0.bits))
+    }
+}
+
+impl<F: Frac> Coord<F> {
+    /// see `Scalar::convert`
+    pub fn convert<G: Frac>(self: Coord<F>) -> Coord<G> {
+        Coord(Inner::new(convert_bits::<F, G>(self.0.bits)))
+    }
+}
+
+// the approximation below is tuned for 16 fractional bits
+impl Scalar<N16> {
+    /// fast approximate square root, only meaningful for values below 2^16
+    /// since the bits are truncated to 32 bits before iterating
+    pub fn rough_sqrt(self: Scalar<N16>) -> Scalar<N16> {
         // this is x * 2 ^ 16
         let bits = self.0.bits;
         // debug assert since newton's method will work... strangely
@@ -23,14 +54,19 @@ impl Scalar {
         let result = rough_sqrt(bits as u32, 8, 6);
         Scalar::from_bits((result as i64) << 8)
     }
+}
 
-    pub fn sqrt(self: Scalar) -> Scalar {
-        self.rough_sqrt()
-    }
-
-    pub fn squared(self: Scalar) -> Scalar {
-        self * self
+fn convert_bits<F: Frac, G: Frac>(bits: i64) -> i64 {
+    if G::BITS < F::BITS {
+        return round_shift(bits as i128, F::BITS - G::BITS) as i64;
     }
+    let shift = G::BITS - F::BITS;
+    let result = bits << shift;
+    debug_assert!(
+        result >> shift == bits,
+        "Scalar overflow: {} * 2 ^ {}", bits, shift
+    );
+    result
 }
 
 // note there is a nice approximation algorithm at
@@ -48,6 +84,27 @@ fn rough_sqrt(val: u32, magnitude: i8, iterations: u8) -> u32 {
     result
 }
 
+// floor of the square root, computed one bit at a time
+pub(crate) fn exact_sqrt(val: u128) -> u128 {
+    let mut remaining = val;
+    let mut result = 0;
+    // highest power of four that fits in the value
+    let mut bit = 1 << 126;
+    while bit > val {
+        bit >>= 2;
+    }
+    while bit != 0 {
+        if remaining >= result + bit {
+            remaining -= result + bit;
+            result = (result >> 1) + bit;
+        } else {
+            result >>= 1;
+        }
+        bit >>= 2;
+    }
+    result
+}
+
 #[cfg(test)]
 mod test_rough_sqrt {
     #[test]
@@ -93,16 +150,69 @@ mod test_rough_sqrt {
     }
 }
 
+#[cfg(test)]
+mod test_sqrt {
+    // checks that root is the nearest value to the true square root,
+    // i.e. (root - 1/2)^2 <= val < (root + 1/2)^2
+    fn test_exact(bits: i64) {
+        let val = ::Scalar::from_bits(bits);
+        let root = val.sqrt().into_bits() as i128;
+
+        // work in units of 2 ^ -17 so that the halves are integers
+        let scaled = (bits as i128) << 18;
+        let lower = (2 * root - 1) * (2 * root - 1);
+        let upper = (2 * root + 1) * (2 * root + 1);
+        assert!(
+            (root == 0 || lower <= scaled) && scaled < upper,
+            "Scalar::sqrt({}) is not correctly rounded, got {}",
+            val, ::Scalar::from_bits(root as i64)
+        );
+    }
+
+    #[test]
+    fn test_small_sqrts_exhaustive() {
+        let max_val: ::Scalar = 8.into();
+        for bits in 0..max_val.into_bits() {
+            test_exact(bits);
+        }
+    }
+
+    #[test]
+    fn test_large_sqrts() {
+        let mut bits: i64 = 1;
+        while bits < i64::MAX / 3 {
+            test_exact(bits - 1);
+            test_exact(bits);
+            test_exact(bits + 1);
+            bits = bits * 3 + 7;
+        }
+        test_exact(i64::MAX);
+    }
+
+    #[test]
+    fn test_perfect_squares() {
+        for i in 0..5000 {
+            let val: ::Scalar = (i * i).into();
+            assert_eq!(val.sqrt(), i);
+        }
+        let far: ::Scalar = 300.into();
+        assert_eq!(far.squared().sqrt(), far);
+        // 2^16 * sqrt(2) = 92681.9...
+        let two: ::Scalar = 2.into();
+        assert_eq!(two.sqrt().into_bits(), 92682);
+    }
+}
+
 
-impl From<i32> for Scalar {
-    fn from(val: i32) -> Scalar {
-        Scalar(Inner::new((val as i64) << 16))
+impl<F: Frac> From<i32> for Scalar<F> {
+    fn from(val: i32) -> Scalar<F> {
+        Scalar(Inner::new((val as i64) << F::BITS))
     }
 }
 
-impl From<Scalar> for i32 {
-    fn from(val: Scalar) -> i32 {
-        (val.0.bits >> 16) as i32
+impl<F: Frac> From<Scalar<F>> for i32 {
+    fn from(val: Scalar<F>) -> i32 {
+        (val.0.bits >> F::BITS) as i32
     }
 }
 
@@ -115,67 +225,79 @@ impl From<Scalar> for f32 {
 }
 */
 
-impl From<Scalar> for f64 {
-    fn from(val: Scalar) -> f64 {
-        val.0.bits as f64 / (1 << 16) as f64
+impl<F: Frac> From<Scalar<F>> for f64 {
+    fn from(val: Scalar<F>) -> f64 {
+        val.0.bits as f64 / (1i64 << F::BITS) as f64
     }
 }
 
 
-impl PartialEq<i32> for Scalar {
-    fn eq(self: &Scalar, other: &i32) -> bool {
-        let scalar_other: Scalar = (*other).into();
+impl<F: Frac> PartialEq<i32> for Scalar<F> {
+    fn eq(self: &Scalar<F>, other: &i32) -> bool {
+        let scalar_other: Scalar<F> = (*other).into();
         *self == scalar_other
     }
 }
 
-impl PartialEq<Scalar> for i32 {
-    fn eq(self: &i32, other: &Scalar) -> bool {
-        let scalar_self: Scalar = (*self).into();
+impl<F: Frac> PartialEq<Scalar<F>> for i32 {
+    fn eq(self: &i32, other: &Scalar<F>) -> bool {
+        let scalar_self: Scalar<F> = (*self).into();
         scalar_self == *other
     }
 }
 
-impl PartialOrd<i32> for Scalar {
-    fn partial_cmp(self: &Scalar, other: &i32) -> Option<cmp::Ordering> {
-        let scalar_other: Scalar = (*other).into();
+impl<F: Frac> PartialOrd<i32> for Scalar<F> {
+    fn partial_cmp(
+        self: &Scalar<F>,
+        other: &i32,
+    ) -> Option<cmp::Ordering> {
+        let scalar_other: Scalar<F> = (*other).into();
         PartialOrd::partial_cmp(self, &scalar_other)
     }
 }
 
-impl PartialOrd<Scalar> for i32 {
-    fn partial_cmp(self: &i32, other: &Scalar) -> Option<cmp::Ordering> {
-        let scalar_self: Scalar = (*self).into();
+impl<F: Frac> PartialOrd<Scalar<F>> for i32 {
+    fn partial_cmp(
+        self: &i32,
+        other: &Scalar<F>,
+    ) -> Option<cmp::Ordering> {
+        let scalar_self: Scalar<F> = (*self).into();
         PartialOrd::partial_cmp(&scalar_self, other)
     }
 }
 
 
-impl fmt::Debug for Coord {
+impl<F: Frac> fmt::Debug for Coord<F> {
     fn fmt(self: &Self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         write!(f, "{}", self)
     }
 }
 
-impl fmt::Debug for Scalar {
+impl<F: Frac> fmt::Debug for Scalar<F> {
     fn fmt(self: &Self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         write!(f, "{}", self)
     }
 }
 
-impl fmt::Display for Coord {
+impl<F: Frac> fmt::Display for Coord<F> {
     fn fmt(self: &Self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         let x = self.0.bits;
-        let hi = x >> 16;
-        let lo_mask = (1 << 16) - 1;
+        // work with the magnitude, so that the fractional part of a negative
+        // number isn't counted upwards from the integer below it
+        if x < 0 {
+            write!(f, "-")?;
+        }
+        let x = x.unsigned_abs();
+        let hi = x >> F::BITS;
+        let lo_mask = (1 << F::BITS) - 1;
         let lo = x & lo_mask;
         write!(f, "{}", hi)?;
         if lo > 0 {
             write!(f, ".")?;
-            let mut remaining = lo.abs();
+            let mut remaining = lo;
             while remaining > 0 {
                 remaining *= 10;
-                write!(f, "{}", remaining >> 16)?;
+                write!(f, "{}", remaining >> F::BITS)?;
                 remaining &= lo_mask;
             }
         }
@@ -183,15 +305,63 @@ impl fmt::Display for Coord {
     }
 }
 
-impl fmt::Display for Scalar {
+impl<F: Frac> fmt::Display for Scalar<F> {
     fn fmt(self: &Self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         if self.0.bits > 0 {
             write!(f, "+")?;
         }
-        let as_coord = Coord::default() + self.clone();
+        let as_coord = Coord::default() + *self;
         write!(f, "{}", as_coord)
     }
 }
 
 
 
+
+#[cfg(test)]
+mod test_precision {
+    use fixed::{Scalar, Coord, Vector, N8, N16, N24};
+    use Angle;
+
+    #[test]
+    fn test_other_precisions() {
+        let fine = Scalar::<N24>::from(3) / 2;
+        assert_eq!(fine.into_bits(), 3 << 23);
+        assert_eq!(format!("{}", fine), "+1.5");
+        assert_eq!(fine * fine, Scalar::from(9) / 4);
+
+        // 2 ^ 24 * sqrt(2) = 23726566.4...
+        let two: Scalar<N24> = 2.into();
+        assert_eq!(two.sqrt().into_bits(), 23726566);
+
+        // 0.1 * 2 ^ 8 = 25.6
+        let coarse: Scalar<N8> = "0.1".parse().unwrap();
+        assert_eq!(coarse.into_bits(), 26);
+    }
+
+    #[test]
+    fn test_convert() {
+        let tiny = Scalar::<N16>::from_bits(1);
+        assert_eq!(tiny.convert::<N24>().into_bits(), 1 << 8);
+        assert_eq!(tiny.convert::<N24>().convert::<N16>(), tiny);
+        // half of the smallest N16 value rounds away from zero
+        let half = Scalar::<N24>::from_bits(1 << 7);
+        assert_eq!(half.convert::<N16>(), tiny);
+        assert_eq!((-half).convert::<N16>(), -tiny);
+        assert_eq!(tiny.convert::<N8>(), 0);
+
+        let pos = Coord::<N16>::default() + Scalar::from(-5) / 4;
+        let back = Coord::<N8>::default() + Scalar::from(-5) / 4;
+        assert_eq!(pos.convert::<N8>(), back);
+    }
+
+    #[test]
+    fn test_fine_vectors() {
+        let v = Vector::<N24> { x: 3.into(), y: 4.into() };
+        assert_eq!(v.magnitude(), 5);
+        assert_eq!(v.with_length(10.into()), v * 2);
+        let up = Vector::<N24>::from_angle(Angle::quarter_turn());
+        assert_eq!(up, Vector { x: 0.into(), y: 1.into() });
+        assert_eq!(up.angle(), Angle::quarter_turn());
+    }
+}
@@ -1,110 +1,122 @@
 use std::ops;
 
-use fix::aliases::binary::IFix64;
-use typenum::N32;
+use fixed::{Frac, Scalar, Coord};
+use scalar_checked::{mul_bits, div_bits, narrow};
 
-use {Scalar, Coord};
-
-impl ops::Neg for Scalar {
+impl<F: Frac> ops::Neg for Scalar<F> {
     type Output = Self;
     fn neg(self: Self) -> Self {
         Scalar(-self.0)
     }
 }
 
-impl ops::Add for Scalar {
-    type Output = Scalar;
-    fn add(mut self: Scalar, other: Scalar) -> Scalar {
+impl<F: Frac> ops::Add for Scalar<F> {
+    type Output = Scalar<F>;
+    fn add(mut self: Scalar<F>, other: Scalar<F>) -> Scalar<F> {
         self += other;
         self
     }
 }
 
-impl ops::Add<Scalar> for Coord {
-    type Output = Coord;
-    fn add(mut self: Coord, other: Scalar) -> Coord {
+impl<F: Frac> ops::Add<Scalar<F>> for Coord<F> {
+    type Output = Coord<F>;
+    fn add(mut self: Coord<F>, other: Scalar<F>) -> Coord<F> {
         self += other;
         self
     }
 }
 
-impl ops::Add<Coord> for Scalar {
-    type Output = Coord;
-    fn add(self: Scalar, mut other: Coord) -> Coord {
+impl<F: Frac> ops::Add<Coord<F>> for Scalar<F> {
+    type Output = Coord<F>;
+    fn add(self: Scalar<F>, mut other: Coord<F>) -> Coord<F> {
         other += self;
         other
     }
 }
 
-impl ops::Sub for Scalar {
-    type Output = Scalar;
-    fn sub(mut self: Scalar, other: Scalar) -> Scalar {
+impl<F: Frac> ops::Sub for Scalar<F> {
+    type Output = Scalar<F>;
+    fn sub(mut self: Scalar<F>, other: Scalar<F>) -> Scalar<F> {
         self -= other;
         self
     }
 }
 
-impl ops::Sub<Scalar> for Coord {
-    type Output = Coord;
-    fn sub(mut self: Coord, other: Scalar) -> Coord {
+impl<F: Frac> ops::Sub<Scalar<F>> for Coord<F> {
+    type Output = Coord<F>;
+    fn sub(mut self: Coord<F>, other: Scalar<F>) -> Coord<F> {
         self -= other;
         self
     }
 }
 
-impl ops::Sub for Coord {
-    type Output = Scalar;
-    fn sub(self: Coord, other: Coord) -> Scalar {
-        Scalar(self.0 - other.0)
+impl<F: Frac> ops::Sub for Coord<F> {
+    type Output = Scalar<F>;
+    fn sub(self: Coord<F>, other: Coord<F>) -> Scalar<F> {
+        let result = self.0.bits.checked_sub(other.0.bits);
+        debug_assert!(
+            result.is_some(),
+            "Coord overflow: {:?} - {:?}", self, other
+        );
+        Scalar::from_bits(self.0.bits.wrapping_sub(other.0.bits))
     }
 }
 
-impl ops::Mul for Scalar {
-    type Output = Scalar;
-    fn mul(self: Scalar, other: Scalar) -> Scalar {
-        let prod = self.0 * other.0;
-        Scalar(prod.convert())
+impl<F: Frac> ops::Mul for Scalar<F> {
+    type Output = Scalar<F>;
+    fn mul(self: Scalar<F>, other: Scalar<F>) -> Scalar<F> {
+        let prod = mul_bits::<F>(self.0.bits, other.0.bits);
+        let result = narrow(prod);
+        debug_assert!(
+            result.is_some(),
+            "Scalar overflow: {:?} * {:?}", self, other
+        );
+        Scalar::from_bits(result.unwrap_or(prod as i64))
     }
 }
 
-impl ops::Div for Scalar {
-    type Output = Scalar;
-    fn div(self: Scalar, other: Scalar) -> Scalar {
-        let nume: IFix64<N32> = self.0.convert();
-        let quot = nume / other.0;
-        Scalar(quot)
+impl<F: Frac> ops::Div for Scalar<F> {
+    type Output = Scalar<F>;
+    fn div(self: Scalar<F>, other: Scalar<F>) -> Scalar<F> {
+        assert!(other.0.bits != 0, "Scalar division by zero");
+        let quot = div_bits::<F>(self.0.bits, other.0.bits);
+        let result = narrow(quot);
+        debug_assert!(
+            result.is_some(),
+            "Scalar overflow: {:?} / {:?}", self, other
+        );
+        Scalar::from_bits(result.unwrap_or(quot as i64))
     }
 }
 
-impl ops::Rem for Scalar {
-    type Output = Scalar;
-    fn rem(mut self: Scalar, other: Scalar) -> Scalar {
+impl<F: Frac> ops::Rem for Scalar<F> {
+    type Output = Scalar<F>;
+    fn rem(mut self: Scalar<F>, other: Scalar<F>) -> Scalar<F> {
         self %= other;
         self
     }
 }
 
-impl ops::Mul<i64> for Scalar {
-    type Output = Scalar;
-    fn mul(mut self: Scalar, other: i64) -> Scalar {
+impl<F: Frac> ops::Mul<i64> for Scalar<F> {
+    type Output = Scalar<F>;
+    fn mul(mut self: Scalar<F>, other: i64) -> Scalar<F> {
         self *= other;
         self
     }
 }
 
-impl ops::Mul<Scalar> for i64 {
-    type Output = Scalar;
-    fn mul(self: i64, mut other: Scalar) -> Scalar {
+impl<F: Frac> ops::Mul<Scalar<F>> for i64 {
+    type Output = Scalar<F>;
+    fn mul(self: i64, mut other: Scalar<F>) -> Scalar<F> {
         other *= self;
         other
     }
 }
 
-impl ops::Div<i64> for Scalar {
-    type Output = Scalar;
-    fn div(mut self: Scalar, other: i64) -> Scalar {
+impl<F: Frac> ops::Div<i64> for Scalar<F> {
+    type Output = Scalar<F>;
+    fn div(mut self: Scalar<F>, other: i64) -> Scalar<F> {
         self /= other;
         self
     }
 }
-
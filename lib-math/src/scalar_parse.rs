@@ -0,0 +1,212 @@
+use std::error;
+use std::fmt;
+use std::str;
+
+use Inner;
+use fixed::{Frac, Scalar, Coord};
+
+/// how to round values that fall between two representable Scalars
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// nearest value, ties away from zero
+    Nearest,
+    /// towards negative infinity
+    Floor,
+    /// towards positive infinity
+    Ceil,
+    TowardZero,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    Empty,
+    InvalidDigit,
+    OutOfRange,
+    /// vectors and positions must look like `(x, y)`
+    Malformed,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(self: &Self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let description = match *self {
+            ParseError::Empty => "cannot parse number from empty string",
+            ParseError::InvalidDigit => "invalid digit found in string",
+            ParseError::OutOfRange => "number too large to fit in Scalar",
+            ParseError::Malformed => "expected a pair of the form (x, y)",
+        };
+        write!(f, "{}", description)
+    }
+}
+
+impl error::Error for ParseError {}
+
+impl<F: Frac> Scalar<F> {
+    /// returns `None` for NaN, infinities and values out of range
+    pub fn from_f64(val: f64, rounding: Rounding) -> Option<Scalar<F>> {
+        // scaling by a power of two is exact
+        let scaled = val * (1i64 << F::BITS) as f64;
+        let rounded = match rounding {
+            Rounding::Nearest => scaled.round(),
+            Rounding::Floor => scaled.floor(),
+            Rounding::Ceil => scaled.ceil(),
+            Rounding::TowardZero => scaled.trunc(),
+        };
+        let limit = 2f64.powi(63);
+        // NaN fails both comparisons
+        if -limit <= rounded && rounded < limit {
+            Some(Scalar::from_bits(rounded as i64))
+        } else {
+            None
+        }
+    }
+}
+
+// any more digits than this can only matter for breaking ties,
+// and the digits that Display writes never get this long
+const MAX_FRAC_DIGITS: usize = 30;
+
+// parses the format written by Display, rounding to the nearest value,
+// with ties away from zero
+fn parse_bits<F: Frac>(s: &str) -> Result<i64, ParseError> {
+    let (negative, digits) = match s.as_bytes().first() {
+        Some(&b'-') => (true, &s[1..]),
+        Some(&b'+') => (false, &s[1..]),
+        _ => (false, s),
+    };
+    let (int_digits, frac_digits) = match digits.find('.') {
+        Some(n) => (&digits[..n], &digits[n + 1..]),
+        None => (digits, ""),
+    };
+    if int_digits.is_empty() && frac_digits.is_empty() {
+        return Err(ParseError::Empty);
+    }
+
+    let mut int_part: u128 = 0;
+    for c in int_digits.chars() {
+        let digit = c.to_digit(10).ok_or(ParseError::InvalidDigit)?;
+        int_part = int_part * 10 + digit as u128;
+        if int_part > 1 << (64 - F::BITS) {
+            return Err(ParseError::OutOfRange);
+        }
+    }
+
+    let mut frac_num: u128 = 0;
+    let mut frac_den: u128 = 1;
+    for (n, c) in frac_digits.chars().enumerate() {
+        let digit = c.to_digit(10).ok_or(ParseError::InvalidDigit)?;
+        if n < MAX_FRAC_DIGITS {
+            frac_num = frac_num * 10 + digit as u128;
+            frac_den *= 10;
+        }
+    }
+
+    // round the fraction to BITS bits, the ignored digits can only push
+    // a value past a tie, which already rounds up
+    let shifted = frac_num << F::BITS;
+    let mut frac_bits = shifted / frac_den;
+    if 2 * (shifted % frac_den) >= frac_den {
+        frac_bits += 1;
+    }
+
+    let magnitude = (int_part << F::BITS) + frac_bits;
+    let limit = if negative { 1 << 63 } else { (1 << 63) - 1 };
+    if magnitude > limit {
+        return Err(ParseError::OutOfRange);
+    }
+    if negative {
+        Ok((magnitude as i64).wrapping_neg())
+    } else {
+        Ok(magnitude as i64)
+    }
+}
+
+impl<F: Frac> str::FromStr for Scalar<F> {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Scalar<F>, ParseError> {
+        parse_bits::<F>(s.trim()).map(Scalar::from_bits)
+    }
+}
+
+impl<F: Frac> str::FromStr for Coord<F> {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Coord<F>, ParseError> {
+        parse_bits::<F>(s.trim()).map(|bits| Coord(Inner::new(bits)))
+    }
+}
+
+#[cfg(test)]
+mod test_parse {
+    use {Scalar, Coord, Rounding, ParseError};
+
+    fn check_round_trip(bits: i64) {
+        let val = Scalar::from_bits(bits);
+        let text = format!("{}", val);
+        assert_eq!(text.parse(), Ok(val), "{} didn't round trip", text);
+
+        let pos = Coord::default() + val;
+        let text = format!("{}", pos);
+        assert_eq!(text.parse(), Ok(pos), "{} didn't round trip", text);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for bits in -70000..70000 {
+            check_round_trip(bits);
+        }
+        let mut bits: i64 = 1;
+        while bits < i64::MAX / 5 {
+            check_round_trip(bits);
+            check_round_trip(-bits);
+            bits = bits * 5 + 3;
+        }
+        check_round_trip(i64::MAX);
+        check_round_trip(i64::MIN);
+    }
+
+    #[test]
+    fn test_parse_decimals() {
+        let half = Scalar::from(1) / 2;
+        assert_eq!("12.5".parse(), Ok(Scalar::from(12) + half));
+        assert_eq!("-0.5".parse(), Ok(-half));
+        assert_eq!("+3".parse(), Ok(Scalar::from(3)));
+        assert_eq!(" .5 ".parse(), Ok(half));
+        // 0.1 is not representable, so it rounds to 6554 / 65536
+        assert_eq!("0.1".parse(), Ok(Scalar::from_bits(6554)));
+        // 2 ^ -17 is exactly half a unit, and rounds away from zero
+        assert_eq!("0.00000762939453125".parse(), Ok(Scalar::from_bits(1)));
+        assert_eq!("-0.00000762939453125".parse(), Ok(Scalar::from_bits(-1)));
+        assert_eq!("0.0000076293945312".parse(), Ok(Scalar::from_bits(0)));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!("".parse::<Scalar>(), Err(ParseError::Empty));
+        assert_eq!("-.".parse::<Scalar>(), Err(ParseError::Empty));
+        assert_eq!("1.2.3".parse::<Scalar>(), Err(ParseError::InvalidDigit));
+        assert_eq!("1e5".parse::<Coord>(), Err(ParseError::InvalidDigit));
+        let huge = "140737488355328";
+        assert_eq!(huge.parse::<Scalar>(), Err(ParseError::OutOfRange));
+        let min = format!("-{}", huge);
+        assert_eq!(min.parse(), Ok(Scalar::from_bits(i64::MIN)));
+    }
+
+    #[test]
+    fn test_from_f64() {
+        let three_quarters = Scalar::from(3) / 4;
+        assert_eq!(Scalar::from_f64(0.75, Rounding::Nearest),
+                   Some(three_quarters));
+
+        let third = 1.0 / 3.0;
+        let below = Scalar::from_bits(21845);
+        let above = Scalar::from_bits(21846);
+        assert_eq!(Scalar::from_f64(third, Rounding::Nearest), Some(below));
+        assert_eq!(Scalar::from_f64(third, Rounding::Floor), Some(below));
+        assert_eq!(Scalar::from_f64(third, Rounding::Ceil), Some(above));
+        assert_eq!(Scalar::from_f64(-third, Rounding::Floor), Some(-above));
+        assert_eq!(Scalar::from_f64(-third, Rounding::TowardZero),
+                   Some(-below));
+
+        assert_eq!(Scalar::from_f64(f64::NAN, Rounding::Nearest), None);
+        assert_eq!(Scalar::from_f64(1e15, Rounding::Nearest), None);
+    }
+}
@@ -0,0 +1,143 @@
+use std::cmp;
+use std::fmt;
+use std::marker;
+use std::ops;
+
+use fixed::{Frac, Scalar, WideScalar};
+use scalar_checked::narrow;
+use scalar_misc::exact_sqrt;
+
+impl<F: Frac> WideScalar<F> {
+    pub fn from_bits(bits: i128) -> WideScalar<F> {
+        WideScalar(bits, marker::PhantomData)
+    }
+
+    pub fn into_bits(self: WideScalar<F>) -> i128 {
+        self.0
+    }
+
+    /// the nearest Scalar towards zero, `None` if it is out of range
+    pub fn narrow(self: WideScalar<F>) -> Option<Scalar<F>> {
+        narrow(self.0 / (1 << F::BITS)).map(Scalar::from_bits)
+    }
+
+    /// square root rounded to the nearest Scalar,
+    /// this always fits since the result has half as many fractional bits
+    pub fn sqrt(self: WideScalar<F>) -> Scalar<F> {
+        debug_assert!(self.0 >= 0, "Square root of negative number");
+        let val = cmp::max(self.0, 0) as u128;
+        let root = exact_sqrt(val);
+        // round up if val is past (root + 1/2)^2 = root^2 + root + 1/4
+        let rounded = if val - root * root > root { root + 1 } else { root };
+        Scalar::from_bits(rounded as i64)
+    }
+
+    /// returns `None` on overflow or division by zero
+    pub fn checked_div(
+        self: WideScalar<F>,
+        other: WideScalar<F>,
+    ) -> Option<Scalar<F>> {
+        if other.0 == 0 {
+            return None;
+        }
+        div_wide(self.0, other.0, F::BITS)
+            .and_then(narrow)
+            .map(Scalar::from_bits)
+    }
+}
+
+// computes x * 2 ^ shift / y truncated towards zero,
+// long division so that the shifted numerator doesn't need to fit anywhere
+fn div_wide(x: i128, y: i128, shift: u32) -> Option<i128> {
+    let negative = (x < 0) != (y < 0);
+    let x_abs = x.unsigned_abs();
+    let y_abs = y.unsigned_abs();
+
+    let mut quot = x_abs / y_abs;
+    let mut rem = x_abs % y_abs;
+    for _ in 0..shift {
+        quot = quot.checked_mul(2)?;
+        // rem < y_abs <= 2 ^ 127, so this can't overflow
+        rem <<= 1;
+        if rem >= y_abs {
+            rem -= y_abs;
+            quot += 1;
+        }
+    }
+
+    if negative {
+        0i128.checked_sub_unsigned(quot)
+    } else if quot <= i128::MAX as u128 {
+        Some(quot as i128)
+    } else {
+        None
+    }
+}
+
+impl<F: Frac> From<Scalar<F>> for WideScalar<F> {
+    fn from(val: Scalar<F>) -> WideScalar<F> {
+        WideScalar::from_bits((val.into_bits() as i128) << F::BITS)
+    }
+}
+
+impl<F: Frac> ops::Neg for WideScalar<F> {
+    type Output = WideScalar<F>;
+    fn neg(self: WideScalar<F>) -> WideScalar<F> {
+        WideScalar::from_bits(-self.0)
+    }
+}
+
+impl<F: Frac> ops::Add for WideScalar<F> {
+    type Output = WideScalar<F>;
+    fn add(self: WideScalar<F>, other: WideScalar<F>) -> WideScalar<F> {
+        WideScalar::from_bits(self.0 + other.0)
+    }
+}
+
+impl<F: Frac> ops::Sub for WideScalar<F> {
+    type Output = WideScalar<F>;
+    fn sub(self: WideScalar<F>, other: WideScalar<F>) -> WideScalar<F> {
+        WideScalar::from_bits(self.0 - other.0)
+    }
+}
+
+impl<F: Frac> ops::Div for WideScalar<F> {
+    type Output = Scalar<F>;
+    fn div(self: WideScalar<F>, other: WideScalar<F>) -> Scalar<F> {
+        assert!(other.0 != 0, "Scalar division by zero");
+        let result = self.checked_div(other);
+        debug_assert!(
+            result.is_some(),
+            "Scalar overflow: {:?} / {:?}", self, other
+        );
+        result.unwrap_or_else(|| {
+            let quot = div_wide(self.0, other.0, F::BITS).unwrap_or(0);
+            Scalar::from_bits(quot as i64)
+        })
+    }
+}
+
+impl<F: Frac> PartialEq<Scalar<F>> for WideScalar<F> {
+    fn eq(self: &WideScalar<F>, other: &Scalar<F>) -> bool {
+        *self == WideScalar::from(*other)
+    }
+}
+
+impl<F: Frac> PartialOrd<Scalar<F>> for WideScalar<F> {
+    fn partial_cmp(
+        self: &WideScalar<F>,
+        other: &Scalar<F>,
+    ) -> Option<cmp::Ordering> {
+        PartialOrd::partial_cmp(self, &WideScalar::from(*other))
+    }
+}
+
+impl<F: Frac> fmt::Debug for WideScalar<F> {
+    fn fmt(self: &Self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        // the extra bits are too fine to write out, so show the nearest Scalar
+        match self.narrow() {
+            Some(val) => write!(f, "{}", val),
+            None => write!(f, "{} * 2^-{}", self.0, 2 * F::BITS),
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use std::fmt;
+use std::marker;
+use std::str;
+
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde::de;
+
+use {Inner, Angle, Rounding};
+use fixed::{Frac, Scalar, Coord};
+
+// human readable formats get the decimal form that Display writes,
+// which is exact, while compact formats get the raw bits
+
+impl<F: Frac> Serialize for Scalar<F> {
+    fn serialize<S>(self: &Self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_i64(self.0.bits)
+        }
+    }
+}
+
+impl<F: Frac> Serialize for Coord<F> {
+    fn serialize<S>(self: &Self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_i64(self.0.bits)
+        }
+    }
+}
+
+impl Serialize for Angle {
+    fn serialize<S>(self: &Self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        // fractions of a turn can't be written out exactly in decimal
+        serializer.serialize_u32(self.0)
+    }
+}
+
+// shared between Scalar and Coord, which only differ in their constructor
+struct BitsVisitor<T> {
+    human_readable: bool,
+    _phantom: marker::PhantomData<T>,
+}
+
+trait FromBits: str::FromStr {
+    type Frac: Frac;
+    fn from_bits(bits: i64) -> Self;
+}
+
+impl<F: Frac> FromBits for Scalar<F> {
+    type Frac = F;
+    fn from_bits(bits: i64) -> Scalar<F> {
+        Scalar::from_bits(bits)
+    }
+}
+
+impl<F: Frac> FromBits for Coord<F> {
+    type Frac = F;
+    fn from_bits(bits: i64) -> Coord<F> {
+        Coord(Inner::new(bits))
+    }
+}
+
+impl<'de, T> de::Visitor<'de> for BitsVisitor<T>
+    where T: FromBits,
+          T::Err: fmt::Display,
+{
+    type Value = T;
+
+    fn expecting(self: &Self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.human_readable {
+            write!(f, "a decimal number")
+        } else {
+            let frac_bits = T::Frac::BITS;
+            let int_bits = 64 - frac_bits;
+            write!(f, "the bits of a {}.{} fixed point number",
+                   int_bits, frac_bits)
+        }
+    }
+
+    fn visit_i64<E>(self: Self, val: i64) -> Result<T, E>
+        where E: de::Error
+    {
+        if !self.human_readable {
+            return Ok(T::from_bits(val));
+        }
+        let limit = 1 << (63 - T::Frac::BITS);
+        if !(-limit..limit).contains(&val) {
+            return Err(E::invalid_value(de::Unexpected::Signed(val), &self));
+        }
+        Ok(T::from_bits(val << T::Frac::BITS))
+    }
+
+    fn visit_u64<E>(self: Self, val: u64) -> Result<T, E>
+        where E: de::Error
+    {
+        if val > i64::MAX as u64 {
+            return Err(E::invalid_value(de::Unexpected::Unsigned(val), &self));
+        }
+        self.visit_i64(val as i64)
+    }
+
+    fn visit_f64<E>(self: Self, val: f64) -> Result<T, E>
+        where E: de::Error
+    {
+        // handwritten numbers like 0.75 are convenient,
+        // but anything else is rounded to the nearest value
+        let scalar = if self.human_readable {
+            Scalar::<T::Frac>::from_f64(val, Rounding::Nearest)
+        } else {
+            None
+        };
+        match scalar {
+            Some(scalar) => Ok(T::from_bits(scalar.into_bits())),
+            None => Err(E::invalid_value(de::Unexpected::Float(val), &self)),
+        }
+    }
+
+    fn visit_str<E>(self: Self, val: &str) -> Result<T, E>
+        where E: de::Error
+    {
+        if !self.human_readable {
+            return Err(E::invalid_type(de::Unexpected::Str(val), &self));
+        }
+        val.parse().map_err(E::custom)
+    }
+}
+
+fn deserialize_bits<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where D: Deserializer<'de>,
+          T: FromBits,
+          T::Err: fmt::Display,
+{
+    let human_readable = deserializer.is_human_readable();
+    let visitor = BitsVisitor { human_readable, _phantom: marker::PhantomData };
+    if human_readable {
+        deserializer.deserialize_any(visitor)
+    } else {
+        deserializer.deserialize_i64(visitor)
+    }
+}
+
+impl<'de, F: Frac> Deserialize<'de> for Scalar<F> {
+    fn deserialize<D>(deserializer: D) -> Result<Scalar<F>, D::Error>
+        where D: Deserializer<'de>
+    {
+        deserialize_bits(deserializer)
+    }
+}
+
+impl<'de, F: Frac> Deserialize<'de> for Coord<F> {
+    fn deserialize<D>(deserializer: D) -> Result<Coord<F>, D::Error>
+        where D: Deserializer<'de>
+    {
+        deserialize_bits(deserializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Angle {
+    fn deserialize<D>(deserializer: D) -> Result<Angle, D::Error>
+        where D: Deserializer<'de>
+    {
+        u32::deserialize(deserializer).map(Angle)
+    }
+}
+
+#[cfg(test)]
+mod test_serde {
+    use serde_test::{Token, Configure, assert_tokens, assert_de_tokens};
+    use serde_test::assert_de_tokens_error;
+
+    use {Scalar, Coord, Vector, Position, Angle};
+
+    #[test]
+    fn test_scalar_forms() {
+        let val = Scalar::from(12) + Scalar::from(1) / 2;
+        assert_tokens(&val.readable(), &[Token::Str("+12.5")]);
+        assert_tokens(&val.compact(), &[Token::I64(819200)]);
+
+        let pos = Coord::default() - val;
+        assert_tokens(&pos.readable(), &[Token::Str("-12.5")]);
+        assert_tokens(&pos.compact(), &[Token::I64(-819200)]);
+    }
+
+    #[test]
+    fn test_readable_numbers() {
+        let val: Scalar = 3.into();
+        assert_de_tokens(&val.readable(), &[Token::I64(3)]);
+        assert_de_tokens(&val.readable(), &[Token::U8(3)]);
+        let three_quarters = Scalar::from(3) / 4;
+        assert_de_tokens(&three_quarters.readable(), &[Token::F64(0.75)]);
+        assert_de_tokens_error::<::serde_test::Readable<Scalar>>(
+            &[Token::Str("1.2.3")],
+            "invalid digit found in string",
+        );
+    }
+
+    #[test]
+    fn test_vectors() {
+        let x = Scalar::from(1) / 4;
+        let y: Scalar = (-2).into();
+        let vel = Vector { x, y };
+        assert_tokens(&vel.compact(), &[
+            Token::Struct { name: "Vector", len: 2 },
+            Token::Str("x"), Token::I64(16384),
+            Token::Str("y"), Token::I64(-131072),
+            Token::StructEnd,
+        ]);
+
+        let pos = Position::default() + vel;
+        assert_tokens(&pos.readable(), &[
+            Token::Struct { name: "Position", len: 2 },
+            Token::Str("x"), Token::Str("0.25"),
+            Token::Str("y"), Token::Str("-2"),
+            Token::StructEnd,
+        ]);
+    }
+
+    #[test]
+    fn test_angle() {
+        let angle = Angle::quarter_turn();
+        assert_tokens(&angle.readable(), &[Token::U32(1 << 30)]);
+    }
+}
@@ -1,58 +1,58 @@
 use std::ops;
 
-use {Vector, Position, Scalar};
+use fixed::{Frac, Vector, Position, Scalar};
 
-impl ops::AddAssign for Vector {
-    fn add_assign(self: &mut Vector, other: Vector) {
+impl<F: Frac> ops::AddAssign for Vector<F> {
+    fn add_assign(self: &mut Vector<F>, other: Vector<F>) {
         self.x += other.x;
         self.y += other.y;
     }
 }
 
-impl ops::AddAssign<Vector> for Position {
-    fn add_assign(self: &mut Position, other: Vector) {
+impl<F: Frac> ops::AddAssign<Vector<F>> for Position<F> {
+    fn add_assign(self: &mut Position<F>, other: Vector<F>) {
         self.x += other.x;
         self.y += other.y;
     }
 }
 
-impl ops::SubAssign for Vector {
-    fn sub_assign(self: &mut Vector, other: Vector) {
+impl<F: Frac> ops::SubAssign for Vector<F> {
+    fn sub_assign(self: &mut Vector<F>, other: Vector<F>) {
         self.x -= other.x;
         self.y -= other.y;
     }
 }
 
-impl ops::SubAssign<Vector> for Position {
-    fn sub_assign(self: &mut Position, other: Vector) {
+impl<F: Frac> ops::SubAssign<Vector<F>> for Position<F> {
+    fn sub_assign(self: &mut Position<F>, other: Vector<F>) {
         self.x -= other.x;
         self.y -= other.y;
     }
 }
 
-impl ops::MulAssign<Scalar> for Vector {
-    fn mul_assign(self: &mut Vector, other: Scalar) {
+impl<F: Frac> ops::MulAssign<Scalar<F>> for Vector<F> {
+    fn mul_assign(self: &mut Vector<F>, other: Scalar<F>) {
         self.x *= other;
         self.y *= other;
     }
 }
 
-impl ops::MulAssign<i64> for Vector {
-    fn mul_assign(self: &mut Vector, other: i64) {
+impl<F: Frac> ops::MulAssign<i64> for Vector<F> {
+    fn mul_assign(self: &mut Vector<F>, other: i64) {
         self.x *= other;
         self.y *= other;
     }
 }
 
-impl ops::DivAssign<Scalar> for Vector {
-    fn div_assign(self: &mut Vector, other: Scalar) {
+impl<F: Frac> ops::DivAssign<Scalar<F>> for Vector<F> {
+    fn div_assign(self: &mut Vector<F>, other: Scalar<F>) {
         self.x /= other;
         self.y /= other;
     }
 }
 
-impl ops::DivAssign<i64> for Vector {
-    fn div_assign(self: &mut Vector, other: i64) {
+impl<F: Frac> ops::DivAssign<i64> for Vector<F> {
+    fn div_assign(self: &mut Vector<F>, other: i64) {
         self.x /= other;
         self.y /= other;
     }
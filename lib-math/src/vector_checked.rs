@@ -0,0 +1,119 @@
+use fixed::{Frac, Vector, Scalar, WideScalar};
+
+impl<F: Frac> Vector<F> {
+    pub fn checked_add(self: Vector<F>, other: Vector<F>) -> Option<Vector<F>> {
+        let x = self.x.checked_add(other.x)?;
+        let y = self.y.checked_add(other.y)?;
+        Some(Vector { x, y })
+    }
+
+    pub fn checked_sub(self: Vector<F>, other: Vector<F>) -> Option<Vector<F>> {
+        let x = self.x.checked_sub(other.x)?;
+        let y = self.y.checked_sub(other.y)?;
+        Some(Vector { x, y })
+    }
+
+    pub fn checked_mul(self: Vector<F>, other: Scalar<F>) -> Option<Vector<F>> {
+        let x = self.x.checked_mul(other)?;
+        let y = self.y.checked_mul(other)?;
+        Some(Vector { x, y })
+    }
+
+    pub fn checked_div(self: Vector<F>, other: Scalar<F>) -> Option<Vector<F>> {
+        let x = self.x.checked_div(other)?;
+        let y = self.y.checked_div(other)?;
+        Some(Vector { x, y })
+    }
+
+    pub fn checked_squared(self: Vector<F>) -> Option<Scalar<F>> {
+        self.wide_squared().narrow()
+    }
+
+    pub fn saturating_add(self: Vector<F>, other: Vector<F>) -> Vector<F> {
+        let x = self.x.saturating_add(other.x);
+        let y = self.y.saturating_add(other.y);
+        Vector { x, y }
+    }
+
+    pub fn saturating_sub(self: Vector<F>, other: Vector<F>) -> Vector<F> {
+        let x = self.x.saturating_sub(other.x);
+        let y = self.y.saturating_sub(other.y);
+        Vector { x, y }
+    }
+
+    pub fn saturating_mul(self: Vector<F>, other: Scalar<F>) -> Vector<F> {
+        let x = self.x.saturating_mul(other);
+        let y = self.y.saturating_mul(other);
+        Vector { x, y }
+    }
+
+    pub fn saturating_div(self: Vector<F>, other: Scalar<F>) -> Vector<F> {
+        let x = self.x.saturating_div(other);
+        let y = self.y.saturating_div(other);
+        Vector { x, y }
+    }
+
+    /// exact inner product, see `Scalar::wide_mul`
+    pub fn wide_inner(self: Vector<F>, other: Vector<F>) -> WideScalar<F> {
+        Scalar::wide_mul(self.x, other.x) + Scalar::wide_mul(self.y, other.y)
+    }
+
+    pub fn wide_squared(self: Vector<F>) -> WideScalar<F> {
+        Vector::wide_inner(self, self)
+    }
+
+    /// exact perp-dot product, see `Vector::cross`
+    pub fn wide_cross(self: Vector<F>, other: Vector<F>) -> WideScalar<F> {
+        Scalar::wide_mul(self.x, other.y) - Scalar::wide_mul(self.y, other.x)
+    }
+}
+
+#[cfg(test)]
+mod test_checked {
+    use {Vector, Scalar};
+
+    #[test]
+    fn test_squared_overflow() {
+        // 2 ^ 24 squared is past the 2 ^ 47 limit of Scalar
+        let far: Scalar = (1 << 24).into();
+        let disp = Vector { x: far, y: far };
+        assert_eq!(disp.checked_squared(), None);
+        assert!(disp.wide_squared() > Scalar::from_bits(i64::MAX));
+
+        // but the ratio of two such squares is fine
+        let half = disp / 2;
+        let quarter = Scalar::from(1) / 4;
+        assert_eq!(half.wide_squared() / disp.wide_squared(), quarter);
+
+        // as is the magnitude
+        let unit: Scalar = (1 << 22).into();
+        let pythag = Vector { x: unit * 3, y: unit * 4 };
+        assert_eq!(pythag.magnitude(), unit * 5);
+
+        // and a cross product that cancels back down to a small value
+        let across = Vector { x: far, y: far + Scalar::from(1) };
+        assert_eq!(Vector::wide_cross(disp, across), Scalar::from(1 << 24));
+    }
+
+    #[test]
+    fn test_checked_matches_ops() {
+        let one = Vector { x: 3.into(), y: (-4).into() };
+        let two = Vector { x: 1.into(), y: 2.into() };
+        let scale: Scalar = 5.into();
+        assert_eq!(one.checked_add(two), Some(one + two));
+        assert_eq!(one.checked_sub(two), Some(one - two));
+        assert_eq!(one.checked_mul(scale), Some(one * scale));
+        assert_eq!(one.checked_div(scale), Some(one / scale));
+        assert_eq!(one.checked_squared(), Some(25.into()));
+        assert_eq!(one.checked_div(Scalar::default()), None);
+    }
+
+    #[test]
+    fn test_saturating() {
+        let max = Scalar::from_bits(i64::MAX);
+        let big: Scalar = 1_000_000_000.into();
+        let one = Vector { x: big, y: -big };
+        let sat = one.saturating_mul(big);
+        assert_eq!(sat, Vector { x: max, y: Scalar::from_bits(i64::MIN) });
+    }
+}
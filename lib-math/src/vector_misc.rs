@@ -1,19 +1,201 @@
-use {Vector, Scalar};
+use Angle;
+use fixed::{Frac, Vector, Scalar, N16};
+use angle_misc::{sin_cos_bits, atan2_bits, from_cordic};
+use angle_misc::{round_shift, PRECISION};
 
-impl Vector {
-    pub fn rough_magnitude(self: Vector) -> Scalar {
+impl Vector<N16> {
+    pub fn rough_magnitude(self: Vector<N16>) -> Scalar<N16> {
         self.squared().rough_sqrt()
     }
+}
 
-    pub fn magnitude(self: Vector) -> Scalar {
-        self.squared().sqrt()
+impl<F: Frac> Vector<F> {
+    pub fn magnitude(self: Vector<F>) -> Scalar<F> {
+        self.wide_squared().sqrt()
     }
 
-    pub fn squared(self: Vector) -> Scalar {
+    pub fn squared(self: Vector<F>) -> Scalar<F> {
         Vector::inner(self, self)
     }
 
-    pub fn inner(self: Vector, other: Vector) -> Scalar {
+    pub fn inner(self: Vector<F>, other: Vector<F>) -> Scalar<F> {
         self.x * other.x + self.y * other.y
     }
+
+    /// the vector rotated a quarter turn, from the x axis towards the y axis
+    pub fn perp(self: Vector<F>) -> Vector<F> {
+        let x = -self.y;
+        let y = self.x;
+        Vector { x, y }
+    }
+
+    /// the perp-dot product, positive when other is a quarter turn or less
+    /// from self, in the same direction as perp
+    pub fn cross(self: Vector<F>, other: Vector<F>) -> Scalar<F> {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// unit vector in the same direction, or the zero vector
+    pub fn normalize(self: Vector<F>) -> Vector<F> {
+        self.with_length(1.into())
+    }
+
+    /// vector in the same direction with the given length,
+    /// the zero vector stays zero
+    pub fn with_length(self: Vector<F>, length: Scalar<F>) -> Vector<F> {
+        let magnitude = self.magnitude();
+        if magnitude == 0 {
+            return Vector::default();
+        }
+        let x = self.x.mul_div(length, magnitude);
+        let y = self.y.mul_div(length, magnitude);
+        Vector { x, y }
+    }
+
+    /// the component of self in the direction of onto,
+    /// zero if onto is the zero vector
+    pub fn project_onto(self: Vector<F>, onto: Vector<F>) -> Vector<F> {
+        let onto_squared = onto.wide_squared();
+        if onto_squared == Scalar::default() {
+            return Vector::default();
+        }
+        let inner = Vector::wide_inner(self, onto);
+        let x = onto.x.mul_ratio(inner, onto_squared);
+        let y = onto.y.mul_ratio(inner, onto_squared);
+        Vector { x, y }
+    }
+
+    /// mirror self in the line perpendicular to normal, as when bouncing off
+    /// a surface with that normal. normal doesn't need to be a unit vector
+    pub fn reflect(self: Vector<F>, normal: Vector<F>) -> Vector<F> {
+        self - self.project_onto(normal) * 2
+    }
+
+    /// unit vector pointing in the given direction
+    pub fn from_angle(angle: Angle) -> Vector<F> {
+        let (cos, sin) = sin_cos_bits(angle);
+        let x = from_cordic(cos);
+        let y = from_cordic(sin);
+        Vector { x, y }
+    }
+
+    /// direction of the vector, zero for the zero vector
+    pub fn angle(self: Vector<F>) -> Angle {
+        atan2_bits(self.y.into_bits(), self.x.into_bits())
+    }
+
+    pub fn rotate(self: Vector<F>, angle: Angle) -> Vector<F> {
+        // use the extra precision of cos and sin, so that the error doesn't
+        // scale with the length of the vector
+        let (cos, sin) = sin_cos_bits(angle);
+        let (cos, sin) = (cos as i128, sin as i128);
+        let (x, y) = (self.x.into_bits() as i128, self.y.into_bits() as i128);
+        let x_rot = round_shift(x * cos - y * sin, PRECISION);
+        let y_rot = round_shift(x * sin + y * cos, PRECISION);
+        let x = Scalar::from_bits(x_rot as i64);
+        let y = Scalar::from_bits(y_rot as i64);
+        Vector { x, y }
+    }
+}
+
+#[cfg(test)]
+mod test_geometry {
+    use {Vector, Scalar};
+
+    fn vec(x: i32, y: i32) -> Vector {
+        Vector { x: x.into(), y: y.into() }
+    }
+
+    #[test]
+    fn test_perp_cross() {
+        let v = vec(3, 4);
+        assert_eq!(v.perp(), vec(-4, 3));
+        assert_eq!(Vector::inner(v, v.perp()), 0);
+        assert_eq!(Vector::cross(v, v.perp()), 25);
+        assert_eq!(Vector::cross(v.perp(), v), -25);
+        assert_eq!(Vector::cross(v, v * 3), 0);
+    }
+
+    #[test]
+    fn test_with_length() {
+        let v = vec(3, 4);
+        assert_eq!(v.with_length(10.into()), vec(6, 8));
+        let unit = v.normalize();
+        let fifth = Scalar::from(1) / 5;
+        assert_eq!(unit, Vector { x: fifth * 3, y: fifth * 4 });
+        assert_eq!(Vector::default().normalize(), Vector::default());
+
+        // large vectors don't overflow while finding their magnitude
+        let far = vec(3 << 22, 4 << 22);
+        assert_eq!(far.with_length(5.into()), vec(3, 4));
+    }
+
+    #[test]
+    fn test_project_reflect() {
+        let v = vec(3, 4);
+        let x_axis = vec(7, 0);
+        assert_eq!(v.project_onto(x_axis), vec(3, 0));
+        assert_eq!(v.project_onto(vec(1, 1)), vec(7, 7) / 2);
+        assert_eq!(v.project_onto(Vector::default()), Vector::default());
+
+        // bouncing off a floor, the normal's length doesn't matter
+        assert_eq!(v.reflect(vec(0, -5)), vec(3, -4));
+        assert_eq!(v.reflect(vec(2, 2)), vec(-4, -3));
+    }
+}
+
+#[cfg(test)]
+mod test_angles {
+    use {Vector, Scalar, Angle};
+
+    fn assert_near(actual: Scalar, expected: Scalar, bits: i64) {
+        let err = (actual.into_bits() - expected.into_bits()).abs();
+        assert!(err <= bits, "{} was not near {}", actual, expected);
+    }
+
+    #[test]
+    fn test_from_angle() {
+        let up = Vector::from_angle(Angle::quarter_turn());
+        assert_eq!(up, Vector { x: 0.into(), y: 1.into() });
+        let eighth = Angle::from_degrees(45.into());
+        let diag = Vector::from_angle(eighth);
+        assert_eq!(diag.x, diag.y);
+        assert_eq!(diag.angle(), eighth);
+        assert_near(diag.magnitude(), 1.into(), 2);
+
+        let back_diag = Vector::from_angle(Angle::from_degrees(135.into()));
+        assert_eq!(back_diag.x, -back_diag.y);
+        assert_near(back_diag.magnitude(), 1.into(), 2);
+    }
+
+    #[test]
+    fn test_angle() {
+        let left = Vector { x: (-3).into(), y: 0.into() };
+        assert_eq!(left.angle(), Angle::half_turn());
+        let down = Vector { x: 0.into(), y: (-7).into() };
+        assert_eq!(down.angle(), -Angle::quarter_turn());
+    }
+
+    #[test]
+    fn test_rotate() {
+        let big: Scalar = 300_000.into();
+        let v = Vector { x: big, y: 0.into() };
+        let quarter = Angle::quarter_turn();
+        assert_eq!(v.rotate(quarter), Vector { x: 0.into(), y: big });
+        assert_eq!(v.rotate(Angle::half_turn()), -v);
+
+        // 100 / sqrt 2 is 70.7107
+        let hundred = Vector { x: 100.into(), y: 0.into() };
+        let diag = hundred.rotate(Angle::from_degrees(45.into()));
+        let expected = Scalar::from_bits(4634095);
+        assert_near(diag.x, expected, 2);
+        assert_near(diag.y, expected, 2);
+        assert_near(diag.magnitude(), 100.into(), 4);
+
+        let w = Vector { x: 3.into(), y: 4.into() };
+        let rotated = w.rotate(Angle::from_degrees(30.into()));
+        assert_eq!(rotated.magnitude(), 5);
+        let back = rotated.rotate(Angle::from_degrees((-30).into()));
+        assert!((back - w).squared() <= Scalar::from_bits(2));
+    }
 }
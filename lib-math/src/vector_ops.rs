@@ -1,8 +1,8 @@
 use std::ops;
 
-use {Vector, Position, Scalar};
+use fixed::{Frac, Vector, Position, Scalar};
 
-impl ops::Neg for Vector {
+impl<F: Frac> ops::Neg for Vector<F> {
     type Output = Self;
     fn neg(self: Self) -> Self {
         let x = -self.x;
@@ -11,98 +11,98 @@ impl ops::Neg for Vector {
     }
 }
 
-impl ops::Add for Vector {
-    type Output = Vector;
-    fn add(mut self: Vector, other: Vector) -> Vector {
+impl<F: Frac> ops::Add for Vector<F> {
+    type Output = Vector<F>;
+    fn add(mut self: Vector<F>, other: Vector<F>) -> Vector<F> {
         self += other;
         self
     }
 }
 
-impl ops::Add<Vector> for Position {
-    type Output = Position;
-    fn add(mut self: Position, other: Vector) -> Position {
+impl<F: Frac> ops::Add<Vector<F>> for Position<F> {
+    type Output = Position<F>;
+    fn add(mut self: Position<F>, other: Vector<F>) -> Position<F> {
         self += other;
         self
     }
 }
 
-impl ops::Add<Position> for Vector {
-    type Output = Position;
-    fn add(self: Vector, mut other: Position) -> Position {
+impl<F: Frac> ops::Add<Position<F>> for Vector<F> {
+    type Output = Position<F>;
+    fn add(self: Vector<F>, mut other: Position<F>) -> Position<F> {
         other += self;
         other
     }
 }
 
-impl ops::Sub for Vector {
-    type Output = Vector;
-    fn sub(mut self: Vector, other: Vector) -> Vector {
+impl<F: Frac> ops::Sub for Vector<F> {
+    type Output = Vector<F>;
+    fn sub(mut self: Vector<F>, other: Vector<F>) -> Vector<F> {
         self -= other;
         self
     }
 }
 
-impl ops::Sub<Vector> for Position {
-    type Output = Position;
-    fn sub(mut self: Position, other: Vector) -> Position {
+impl<F: Frac> ops::Sub<Vector<F>> for Position<F> {
+    type Output = Position<F>;
+    fn sub(mut self: Position<F>, other: Vector<F>) -> Position<F> {
         self -= other;
         self
     }
 }
 
-impl ops::Sub for Position {
-    type Output = Vector;
-    fn sub(self: Position, other: Position) -> Vector {
+impl<F: Frac> ops::Sub for Position<F> {
+    type Output = Vector<F>;
+    fn sub(self: Position<F>, other: Position<F>) -> Vector<F> {
         let x = self.x - other.x;
         let y = self.y - other.y;
         Vector { x, y }
     }
 }
 
-impl ops::Mul<Scalar> for Vector {
-    type Output = Vector;
-    fn mul(mut self: Vector, other: Scalar) -> Vector {
+impl<F: Frac> ops::Mul<Scalar<F>> for Vector<F> {
+    type Output = Vector<F>;
+    fn mul(mut self: Vector<F>, other: Scalar<F>) -> Vector<F> {
         self *= other;
         self
     }
 }
 
-impl ops::Mul<Vector> for Scalar {
-    type Output = Vector;
-    fn mul(self: Scalar, mut other: Vector) -> Vector {
+impl<F: Frac> ops::Mul<Vector<F>> for Scalar<F> {
+    type Output = Vector<F>;
+    fn mul(self: Scalar<F>, mut other: Vector<F>) -> Vector<F> {
         other *= self;
         other
     }
 }
 
-impl ops::Mul<i64> for Vector {
-    type Output = Vector;
-    fn mul(mut self: Vector, other: i64) -> Vector {
+impl<F: Frac> ops::Mul<i64> for Vector<F> {
+    type Output = Vector<F>;
+    fn mul(mut self: Vector<F>, other: i64) -> Vector<F> {
         self *= other;
         self
     }
 }
 
-impl ops::Mul<Vector> for i64 {
-    type Output = Vector;
-    fn mul(self: i64, mut other: Vector) -> Vector {
+impl<F: Frac> ops::Mul<Vector<F>> for i64 {
+    type Output = Vector<F>;
+    fn mul(self: i64, mut other: Vector<F>) -> Vector<F> {
         other *= self;
         other
     }
 }
 
-impl ops::Div<Scalar> for Vector {
-    type Output = Vector;
-    fn div(mut self: Vector, other: Scalar) -> Vector {
+impl<F: Frac> ops::Div<Scalar<F>> for Vector<F> {
+    type Output = Vector<F>;
+    fn div(mut self: Vector<F>, other: Scalar<F>) -> Vector<F> {
         self /= other;
         self
     }
 }
 
-impl ops::Div<i64> for Vector {
-    type Output = Vector;
-    fn div(mut self: Vector, other: i64) -> Vector {
+impl<F: Frac> ops::Div<i64> for Vector<F> {
+    type Output = Vector<F>;
+    fn div(mut self: Vector<F>, other: i64) -> Vector<F> {
         self /= other;
         self
     }
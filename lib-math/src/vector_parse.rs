@@ -0,0 +1,79 @@
+use std::fmt;
+use std::str;
+
+use fixed::{Frac, Vector, Position};
+use scalar_parse::ParseError;
+
+impl<F: Frac> fmt::Display for Vector<F> {
+    fn fmt(self: &Self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl<F: Frac> fmt::Display for Position<F> {
+    fn fmt(self: &Self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+// splits "(x, y)" into "x" and "y"
+fn split_pair(s: &str) -> Result<(&str, &str), ParseError> {
+    let s = s.trim();
+    if !s.starts_with('(') || !s.ends_with(')') || s.len() < 2 {
+        return Err(ParseError::Malformed);
+    }
+    let inner = &s[1..s.len() - 1];
+    let mut parts = inner.split(',');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(x), Some(y), None) => Ok((x, y)),
+        _ => Err(ParseError::Malformed),
+    }
+}
+
+impl<F: Frac> str::FromStr for Vector<F> {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Vector<F>, ParseError> {
+        let (x, y) = split_pair(s)?;
+        let x = x.parse()?;
+        let y = y.parse()?;
+        Ok(Vector { x, y })
+    }
+}
+
+impl<F: Frac> str::FromStr for Position<F> {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Position<F>, ParseError> {
+        let (x, y) = split_pair(s)?;
+        let x = x.parse()?;
+        let y = y.parse()?;
+        Ok(Position { x, y })
+    }
+}
+
+#[cfg(test)]
+mod test_parse {
+    use {Vector, Position, Scalar, ParseError};
+
+    #[test]
+    fn test_round_trip() {
+        let x = Scalar::from(12) + Scalar::from(1) / 2;
+        let y = -Scalar::from(3) / 4;
+        let vel = Vector { x, y };
+        assert_eq!(format!("{}", vel), "(+12.5, -0.75)");
+        assert_eq!(format!("{}", vel).parse(), Ok(vel));
+
+        let pos = Position::default() + vel;
+        assert_eq!(format!("{}", pos), "(12.5, -0.75)");
+        assert_eq!(format!("{}", pos).parse(), Ok(pos));
+    }
+
+    #[test]
+    fn test_parse() {
+        let vel = Vector { x: 0.into(), y: (-2).into() };
+        assert_eq!(" ( 0 ,-2 ) ".parse(), Ok(vel));
+        assert_eq!("0, -2".parse::<Vector>(), Err(ParseError::Malformed));
+        assert_eq!("(0)".parse::<Position>(), Err(ParseError::Malformed));
+        assert_eq!("(1, 2, 3)".parse::<Vector>(), Err(ParseError::Malformed));
+        assert_eq!("(1, x)".parse::<Vector>(), Err(ParseError::InvalidDigit));
+    }
+}
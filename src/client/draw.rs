@@ -19,6 +19,9 @@ pub fn draw(
         Player(ref player) => {
             player.draw(time, trans, graphics);
         },
+        Wall(ref wall) => {
+            wall.draw(time, trans, graphics);
+        },
     }
 }
 
@@ -47,12 +50,42 @@ impl Draw for entities::player::Image {
     ) {
         // draw a circle at this new center
         let color = [1.0, 0.0, 0.0, 1.0];
-        let radius = 10.0;
+        let radius = self.radius.as_scalar().into();
         let circle = Circle { color, radius };
         circle.draw(time, center, graphics);
     }
 }
 
+impl Draw for entities::wall::Image {
+    fn draw(
+        self: &Self,
+        _time: units::Time,
+        trans: app::math::Matrix2d,
+        graphics: &mut app::G2d,
+    ) {
+        let color = [0.5, 0.5, 0.5, 1.0];
+        match self.shape {
+            space::Shape::Segment(start, end) => {
+                let start = floatify_displacement(start);
+                let end = floatify_displacement(end);
+                let line = [start[0], start[1], end[0], end[1]];
+                app::line(color, 1.0, line, trans, graphics);
+            },
+            space::Shape::Polygon(ref vertices) => {
+                let points: Vec<[f64; 2]> = vertices
+                    .iter()
+                    .map(|&vertex| floatify_displacement(vertex))
+                    .collect();
+                app::polygon(color, &points, trans, graphics);
+            },
+            space::Shape::Circle(_) => unreachable!(),
+        }
+    }
+}
+
+fn floatify_displacement(displacement: units::Displacement) -> [f64; 2] {
+    [displacement.x.into(), displacement.y.into()]
+}
 
 fn floatify_position(position: units::Position) -> [f64; 2] {
     let origin: units::Position = Default::default();
@@ -33,14 +33,30 @@ fn server_init(
     let (player_send_upd, recv_upd) = mpsc::channel();
     {
         let position = Default::default();
-        player::Player::new(space, time, matter, position, player_send_upd);
+        let radius = 10.into();
+        player::Player::new(
+            space,
+            time,
+            matter,
+            position,
+            radius,
+            player_send_upd,
+        );
     }
 
     let (other_send_upd, recv_other) = mpsc::channel();
     {
         let displacement = units::Displacement { x: 200.into(), y: 0.into() };
         let position = units::Position::default() + displacement;
-        player::Player::new(space, time, matter, position, other_send_upd);
+        let radius = 10.into();
+        player::Player::new(
+            space,
+            time,
+            matter,
+            position,
+            radius,
+            other_send_upd,
+        );
     }
 
     ClientData { recv_upd, recv_other }